@@ -1,45 +1,168 @@
 use darling::ast;
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
+use proc_macro2::Span;
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
 use syn::parse_quote;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::visit_mut::{self, VisitMut};
 
-use crate::{BorrowConf, Input};
+use crate::{BorrowConf, CHeaderConf, Input, NewtypeInput};
+
+/// Adds `F: Aligned` bounds for fields that use generic parameters, so that
+/// missing bounds are reported as ordinary trait errors.
+pub fn bound_generic_fields(input: &mut Input) {
+    let generic_params = generic_params(&input.generics);
+    let ast::Data::Enum(variants) = &input.data else { unreachable!() };
+    let mut predicates = variants
+        .iter()
+        .filter_map(|variant| variant.fields.iter().next())
+        .filter(|field| uses_params(&field.ty, &generic_params))
+        .map(|field| {
+            let field_type = &field.ty;
+            let predicate: syn::WherePredicate = parse_quote!(#field_type: ::enum_ptr::Aligned);
+            predicate
+        })
+        .peekable();
+    if predicates.peek().is_some() {
+        let where_clause = input.generics.make_where_clause();
+        where_clause.predicates.extend(predicates);
+    }
+}
+
+fn generic_params(generics: &syn::Generics) -> Vec<syn::Ident> {
+    let type_params = generics.type_params().map(|param| param.ident.clone());
+    let const_params = generics.const_params().map(|param| param.ident.clone());
+    let mut params: Vec<_> = type_params.chain(const_params).collect();
+    // `Self` mentions all of them.
+    if !params.is_empty() {
+        params.push(syn::Ident::new("Self", Span::call_site()));
+    }
+    params
+}
+
+fn uses_params(ty: &syn::Type, params: &[syn::Ident]) -> bool {
+    struct Finder<'a> {
+        params: &'a [syn::Ident],
+        found: bool,
+    }
+
+    impl<'ast> Visit<'ast> for Finder<'_> {
+        fn visit_path(&mut self, path: &'ast syn::Path) {
+            if let Some(segment) = path.segments.first() {
+                self.found |= path.leading_colon.is_none() && self.params.contains(&segment.ident);
+            }
+            visit::visit_path(self, path);
+        }
+    }
+
+    let mut finder = Finder {
+        params,
+        found: false,
+    };
+    finder.visit_type(ty);
+    finder.found
+}
+
+/// Replaces `Self` with the given type, which is not in scope of free items.
+struct ReplaceSelf(syn::Type);
+
+impl VisitMut for ReplaceSelf {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        match ty {
+            syn::Type::Path(path) if path.qself.is_none() && path.path.is_ident("Self") => {
+                *ty = self.0.clone();
+            }
+            _ => visit_mut::visit_type_mut(self, ty),
+        }
+    }
+}
+
+/// Replaces all lifetimes with `'static`. Alignments don't depend on them.
+struct EraseLifetimes;
+
+impl VisitMut for EraseLifetimes {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        *lifetime = syn::Lifetime::new("'static", lifetime.span());
+    }
+
+    fn visit_bound_lifetimes_mut(&mut self, _: &mut syn::BoundLifetimes) {}
+}
+
+// Tags are assigned by variant order, so the mask only depends on the count.
+fn tag_mask_of<T>(variants: &[T]) -> usize {
+    variants.len().next_power_of_two() - 1
+}
 
 pub fn gen_basic(input: &Input) -> TokenStream {
     let input_ident = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let original_type = quote!(#input_ident #ty_generics);
     let compact_type = quote!(::enum_ptr::Compact<#original_type>);
-    let inner_type = match input.copy.is_present() {
-        true => quote!(::enum_ptr::CompactInnerCopy<#original_type>),
-        false => quote!(::enum_ptr::CompactInner<#original_type>),
+    let inner_type = match (input.copy.is_present(), input.iterative_drop.is_present()) {
+        (true, _) => quote!(::enum_ptr::CompactInnerCopy<#original_type>),
+        (false, true) => quote!(::enum_ptr::CompactInnerDeep<#original_type>),
+        (false, false) => quote!(::enum_ptr::CompactInner<#original_type>),
     };
 
     let ast::Data::Enum(variants) = &input.data else { unreachable!() };
-    let min_align = variants.len().next_power_of_two();
-    let tag_mask = min_align - 1;
+    let tag_mask = tag_mask_of(variants);
+    let min_align = tag_mask + 1;
+    let variant_count = variants.len();
+    let variant_names = variants
+        .iter()
+        .map(|variant| variant.ident.unraw().to_string());
+    let variant_alignments = variants.iter().map(|variant| {
+        let field_type = &variant.fields.iter().next().unwrap().ty;
+        quote!(<#field_type as ::enum_ptr::Aligned>::ALIGNMENT)
+    });
+    let generic_params = generic_params(&input.generics);
     let mut asserts = Vec::new();
+    let mut static_asserts = Vec::new();
     for variant in variants {
         let variant_ident = &variant.ident;
         let field_type = &variant.fields.iter().next().unwrap().ty;
         let assert_msg = format!("`{input_ident}::{variant_ident}` has no enough alignment");
-        // TODO: change to static asserts when available
-        asserts.push(quote! {
-            assert!(
-                <#field_type as ::enum_ptr::Aligned>::ALIGNMENT >= #min_align,
-                #assert_msg
-            );
-        });
+        // Fields without generic parameters are checked eagerly. Others can
+        // only be checked after monomorphization.
+        match uses_params(field_type, &generic_params) {
+            true => asserts.push(quote_spanned! {field_type.span()=>
+                assert!(
+                    <#field_type as ::enum_ptr::Aligned>::ALIGNMENT >= #min_align,
+                    #assert_msg
+                );
+            }),
+            false => {
+                let mut field_type = field_type.clone();
+                ReplaceSelf(parse_quote!(#original_type)).visit_type_mut(&mut field_type);
+                EraseLifetimes.visit_type_mut(&mut field_type);
+                static_asserts.push(quote_spanned! {field_type.span()=>
+                    const _: () = assert!(
+                        <#field_type as ::enum_ptr::Aligned>::ALIGNMENT >= #min_align,
+                        #assert_msg
+                    );
+                });
+            }
+        }
     }
 
     quote! {
+        #(#static_asserts)*
+
         unsafe impl #impl_generics ::enum_ptr::Compactable for #original_type #where_clause {
             type Inner = #inner_type;
 
+            const VARIANT_COUNT: usize = #variant_count;
+
             const MASK: usize = {
                 #(#asserts)*
                 #tag_mask
             };
+
+            const VARIANT_NAMES: &'static [&'static str] = &[#(#variant_names),*];
+
+            const VARIANT_ALIGNMENTS: &'static [usize] = &[#(#variant_alignments),*];
         }
 
         impl #impl_generics From<#original_type> for #compact_type #where_clause {
@@ -59,6 +182,26 @@ pub fn gen_basic(input: &Input) -> TokenStream {
     .into()
 }
 
+pub fn gen_relocatable(input: &Input) -> TokenStream {
+    let input_ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let original_type = quote!(#input_ident #ty_generics);
+
+    let ast::Data::Enum(variants) = &input.data else { unreachable!() };
+    let targets = variants.iter().map(|variant| {
+        let field_type = &variant.fields.iter().next().unwrap().ty;
+        quote!(<#field_type as ::enum_ptr::Relocatable>::TARGET)
+    });
+
+    quote! {
+        unsafe impl #impl_generics ::enum_ptr::CompactRelocatable for #original_type #where_clause {
+            const TARGETS: &'static [::core::option::Option<::core::alloc::Layout>] =
+                &[#(#targets),*];
+        }
+    }
+    .into()
+}
+
 pub fn gen_borrow(input: &Input, conf: &BorrowConf) -> TokenStream {
     let input_ident = &input.ident;
     let input_vis = &input.vis;
@@ -202,3 +345,342 @@ pub fn gen_borrow_mut(input: &Input, conf: &BorrowConf) -> TokenStream {
     }
     .into()
 }
+
+pub fn gen_as_dyn(input: &Input, paths: &[syn::Path]) -> TokenStream {
+    let input_ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let original_type = quote!(#input_ident #ty_generics);
+
+    let mut dyn_generics = input.generics.clone();
+    dyn_generics.params.insert(0, parse_quote!('enum_ptr));
+    dyn_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(Self: 'enum_ptr));
+    let (dyn_impl_generics, _, dyn_where_clause) = dyn_generics.split_for_impl();
+
+    let ast::Data::Enum(variants) = &input.data else { unreachable!() };
+    let mut output = TokenStream::new();
+    for path in paths {
+        let dyn_type = quote!(dyn #path + 'enum_ptr);
+        let match_arms = variants.iter().map(|variant| {
+            let variant_ident = &variant.ident;
+            quote! {
+                Self::#variant_ident(inner) => {
+                    let target: &(#dyn_type) = ::enum_ptr::FieldDeref::force_deref(inner);
+                    target
+                }
+            }
+        });
+        output.extend(TokenStream::from(quote! {
+            impl #dyn_impl_generics ::enum_ptr::CompactAsDyn<#dyn_type> for #original_type
+            #dyn_where_clause
+            {
+                #[inline]
+                fn as_dyn(compact: &::enum_ptr::Compact<Self>) -> &(#dyn_type) {
+                    unsafe {
+                        compact.map_ref(|tmp| match tmp {
+                            #(#match_arms)*
+                        })
+                    }
+                }
+            }
+        }));
+    }
+    output
+}
+
+pub fn gen_as_dyn_mut(input: &Input, paths: &[syn::Path]) -> TokenStream {
+    let input_ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let original_type = quote!(#input_ident #ty_generics);
+
+    let mut dyn_generics = input.generics.clone();
+    dyn_generics.params.insert(0, parse_quote!('enum_ptr));
+    dyn_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(Self: 'enum_ptr));
+    let (dyn_impl_generics, _, dyn_where_clause) = dyn_generics.split_for_impl();
+
+    let ast::Data::Enum(variants) = &input.data else { unreachable!() };
+    let mut output = TokenStream::new();
+    for path in paths {
+        let dyn_type = quote!(dyn #path + 'enum_ptr);
+        let match_arms = variants.iter().map(|variant| {
+            let variant_ident = &variant.ident;
+            quote! {
+                Self::#variant_ident(inner) => {
+                    let target: &mut (#dyn_type) =
+                        ::enum_ptr::FieldDerefMut::force_deref_mut(inner);
+                    target
+                }
+            }
+        });
+        output.extend(TokenStream::from(quote! {
+            impl #dyn_impl_generics ::enum_ptr::CompactAsDynMut<#dyn_type> for #original_type
+            #dyn_where_clause
+            {
+                #[inline]
+                fn as_dyn_mut(compact: &mut ::enum_ptr::Compact<Self>) -> &mut (#dyn_type) {
+                    unsafe {
+                        compact.map_mut(|tmp| match tmp {
+                            #(#match_arms)*
+                        })
+                    }
+                }
+            }
+        }));
+    }
+    output
+}
+
+pub fn gen_c_header(input: &Input, conf: &CHeaderConf) -> TokenStream {
+    let input_ident = &input.ident;
+    let input_vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let ast::Data::Enum(variants) = &input.data else { unreachable!() };
+    let prefix = match &conf.prefix {
+        Some(prefix) => prefix.clone(),
+        None => screaming_snake_case(&input_ident.to_string()),
+    };
+    let func_prefix = prefix.to_lowercase();
+
+    let mut header = format!("#ifndef {prefix}_H\n#define {prefix}_H\n\n#include <stdint.h>\n\n");
+    for (tag, variant) in variants.iter().enumerate() {
+        let variant_name = screaming_snake_case(&variant.ident.to_string());
+        header += &format!("#define {prefix}_TAG_{variant_name} ((uintptr_t){tag})\n");
+    }
+    header += &format!(
+        "#define {prefix}_MASK ((uintptr_t){mask})\n\n\
+         static inline uintptr_t {func_prefix}_tag_of(const void *compact) {{\n    \
+         return (uintptr_t)compact & {prefix}_MASK;\n}}\n\n\
+         static inline void *{func_prefix}_untag(const void *compact) {{\n    \
+         return (void *)((uintptr_t)compact & ~{prefix}_MASK);\n}}\n\n\
+         #endif /* {prefix}_H */\n",
+        mask = tag_mask_of(variants),
+    );
+
+    quote! {
+        impl #impl_generics #input_ident #ty_generics #where_clause {
+            /// C header with the tags and the mask of the compact representation.
+            #input_vis const C_HEADER: &'static str = #header;
+        }
+    }
+    .into()
+}
+
+pub fn gen_match_macro(input: &Input) -> TokenStream {
+    let input_ident = &input.ident;
+    let input_vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let original_type = quote!(#input_ident #ty_generics);
+    let tag_ident = format_ident!("{input_ident}Tag");
+    let macro_ident = format_ident!(
+        "match_{}",
+        screaming_snake_case(&input_ident.to_string()).to_lowercase()
+    );
+
+    let ast::Data::Enum(variants) = &input.data else { unreachable!() };
+    let variant_idents: Vec<_> = variants.iter().map(|variant| &variant.ident).collect();
+    let tags = 0..variants.len();
+    let tag_doc = format!("Tags of [`{input_ident}`], used by `{macro_ident}!`.");
+
+    quote! {
+        #[doc = #tag_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(usize)]
+        #input_vis enum #tag_ident {
+            #(#variant_idents),*
+        }
+
+        impl #tag_ident {
+            /// Returns the tag of a compact value.
+            #[inline]
+            #input_vis fn of #impl_generics (
+                compact: &::enum_ptr::Compact<#original_type>,
+            ) -> Self #where_clause {
+                match compact.tag() {
+                    #(#tags => Self::#variant_idents,)*
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        #[allow(unused_macros)]
+        macro_rules! #macro_ident {
+            ($compact:expr, {
+                $($variant:ident($pat:pat) => $body:expr),+ $(, _ => $default:expr)? $(,)?
+            }) => {{
+                let compact = &$compact;
+                match #tag_ident::of(compact) {
+                    $(#tag_ident::$variant => {
+                        let $pat = unsafe {
+                            ::enum_ptr::get_ref_helper(compact, |tmp| match tmp {
+                                #input_ident::$variant(inner) => Some(inner),
+                                #[allow(unreachable_patterns)]
+                                _ => None,
+                            })
+                            .unwrap_unchecked()
+                        };
+                        $body
+                    })+
+                    $(_ => $default,)?
+                }
+            }};
+            (mut $compact:expr, {
+                $($variant:ident($pat:pat) => $body:expr),+ $(, _ => $default:expr)? $(,)?
+            }) => {{
+                let compact = &mut $compact;
+                match #tag_ident::of(compact) {
+                    $(#tag_ident::$variant => {
+                        let $pat = unsafe {
+                            ::enum_ptr::get_mut_helper(compact, |tmp| match tmp {
+                                #input_ident::$variant(inner) => Some(inner),
+                                #[allow(unreachable_patterns)]
+                                _ => None,
+                            })
+                            .unwrap_unchecked()
+                        };
+                        $body
+                    })+
+                    $(_ => $default,)?
+                }
+            }};
+        }
+    }
+    .into()
+}
+
+pub fn gen_compact(args: proc_macro2::TokenStream, input: &syn::DeriveInput) -> TokenStream {
+    let options = (!args.is_empty()).then(|| quote!(#[enum_ptr(#args)]));
+
+    quote! {
+        #[derive(::enum_ptr::EnumPtr)]
+        #options
+        #[repr(C, usize)]
+        #input
+    }
+    .into()
+}
+
+fn screaming_snake_case(ident: &str) -> String {
+    let mut result = String::new();
+    let mut prev_lower = false;
+    for c in ident.trim_start_matches("r#").chars() {
+        if c.is_uppercase() && prev_lower {
+            result.push('_');
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        result.extend(c.to_uppercase());
+    }
+    result
+}
+
+fn newtype_field(input: &NewtypeInput) -> (syn::Member, &syn::Type) {
+    let ast::Data::Struct(fields) = &input.data else { unreachable!() };
+    let field = fields.iter().next().unwrap();
+    let member = match &field.ident {
+        Some(ident) => syn::Member::Named(ident.clone()),
+        None => syn::Member::Unnamed(0.into()),
+    };
+    (member, &field.ty)
+}
+
+pub fn gen_aligned(input: &NewtypeInput) -> TokenStream {
+    let input_ident = &input.ident;
+    let (_, field_type) = newtype_field(input);
+
+    let mut generics = input.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(parse_quote!(#field_type: ::enum_ptr::Aligned));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let field_alignment = quote!(<#field_type as ::enum_ptr::Aligned>::ALIGNMENT);
+    let mut static_assert = None;
+    let alignment = match &input.min {
+        Some(min) => {
+            let assert_msg = format!("`{input_ident}` has no enough alignment");
+            // Fields without generic parameters are checked eagerly. Others can
+            // only be checked after monomorphization.
+            match uses_params(field_type, &generic_params(&input.generics)) {
+                true => quote! {{
+                    assert!(#field_alignment >= #min, #assert_msg);
+                    #field_alignment
+                }},
+                false => {
+                    let mut field_type = field_type.clone();
+                    ReplaceSelf(parse_quote!(#input_ident #ty_generics))
+                        .visit_type_mut(&mut field_type);
+                    EraseLifetimes.visit_type_mut(&mut field_type);
+                    static_assert = Some(quote_spanned! {field_type.span()=>
+                        const _: () = assert!(
+                            <#field_type as ::enum_ptr::Aligned>::ALIGNMENT >= #min,
+                            #assert_msg
+                        );
+                    });
+                    field_alignment
+                }
+            }
+        }
+        None => field_alignment,
+    };
+
+    quote! {
+        #static_assert
+
+        unsafe impl #impl_generics ::enum_ptr::Aligned for #input_ident #ty_generics #where_clause {
+            const ALIGNMENT: usize = #alignment;
+        }
+    }
+    .into()
+}
+
+pub fn gen_field_deref(input: &NewtypeInput) -> TokenStream {
+    let input_ident = &input.ident;
+    let (field_member, field_type) = newtype_field(input);
+
+    let mut generics = input.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(parse_quote!(#field_type: ::enum_ptr::FieldDeref));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        unsafe impl #impl_generics ::enum_ptr::FieldDeref for #input_ident #ty_generics #where_clause {
+            type Target<'enum_ptr> = <#field_type as ::enum_ptr::FieldDeref>::Target<'enum_ptr>
+            where
+                Self: 'enum_ptr;
+
+            #[inline]
+            fn deref(&self) -> Self::Target<'_> {
+                ::enum_ptr::FieldDeref::deref(&self.#field_member)
+            }
+        }
+    }
+    .into()
+}
+
+pub fn gen_field_deref_mut(input: &NewtypeInput) -> TokenStream {
+    let input_ident = &input.ident;
+    let (field_member, field_type) = newtype_field(input);
+
+    let mut generics = input.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(parse_quote!(#field_type: ::enum_ptr::FieldDerefMut));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        unsafe impl #impl_generics ::enum_ptr::FieldDerefMut for #input_ident #ty_generics #where_clause {
+            type Target<'enum_ptr> = <#field_type as ::enum_ptr::FieldDerefMut>::Target<'enum_ptr>
+            where
+                Self: 'enum_ptr;
+
+            #[inline]
+            fn deref_mut(&mut self) -> Self::Target<'_> {
+                ::enum_ptr::FieldDerefMut::deref_mut(&mut self.#field_member)
+            }
+        }
+    }
+    .into()
+}
@@ -10,8 +10,14 @@ pub struct Input {
     pub data: ast::Data<Variant, ()>,
 
     pub copy: util::Flag,
+    pub iterative_drop: util::Flag,
+    pub relocatable: util::Flag,
     pub borrow: Option<util::Override<BorrowConf>>,
     pub borrow_mut: Option<util::Override<BorrowConf>>,
+    pub as_dyn: Option<util::PathList>,
+    pub as_dyn_mut: Option<util::PathList>,
+    pub c_header: Option<util::Override<CHeaderConf>>,
+    pub match_macro: util::Flag,
 }
 
 #[derive(FromVariant)]
@@ -28,6 +34,7 @@ pub struct Variant {
 
 #[derive(FromField)]
 pub struct Field {
+    pub ident: Option<syn::Ident>,
     pub ty: syn::Type,
 }
 
@@ -36,3 +43,19 @@ pub struct BorrowConf {
     pub name: Option<String>,
     pub derive: Option<syn::Meta>,
 }
+
+#[derive(FromMeta, Default, Clone)]
+pub struct CHeaderConf {
+    pub prefix: Option<String>,
+}
+
+#[derive(FromDeriveInput)]
+#[darling(supports(struct_newtype, struct_named), attributes(aligned), forward_attrs(repr))]
+pub struct NewtypeInput {
+    pub ident: syn::Ident,
+    pub generics: syn::Generics,
+    pub attrs: Vec<syn::Attribute>,
+    pub data: ast::Data<(), Field>,
+
+    pub min: Option<syn::LitInt>,
+}
@@ -19,17 +19,89 @@ pub fn enum_ptr(input: TokenStream) -> TokenStream {
 }
 
 fn enum_ptr_inner(input: &syn::DeriveInput) -> Result<TokenStream, Error> {
-    let input = Input::from_derive_input(input)?;
+    let mut input = Input::from_derive_input(input)?;
 
     validate_input(&input)?;
+    bound_generic_fields(&mut input);
 
     let mut output = gen_basic(&input);
+    if input.relocatable.is_present() {
+        output.extend(gen_relocatable(&input));
+    }
     if let Some(conf) = input.borrow.clone() {
         output.extend(gen_borrow(&input, &conf.unwrap_or_default()));
     }
     if let Some(conf) = input.borrow_mut.clone() {
         output.extend(gen_borrow_mut(&input, &conf.unwrap_or_default()));
     }
+    if let Some(paths) = &input.as_dyn {
+        output.extend(gen_as_dyn(&input, paths));
+    }
+    if let Some(paths) = &input.as_dyn_mut {
+        output.extend(gen_as_dyn_mut(&input, paths));
+    }
+    if let Some(conf) = input.c_header.clone() {
+        output.extend(gen_c_header(&input, &conf.unwrap_or_default()));
+    }
+    if input.match_macro.is_present() {
+        output.extend(gen_match_macro(&input));
+    }
 
     Ok(output)
 }
+
+#[proc_macro_attribute]
+pub fn compact(args: TokenStream, input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match compact_inner(args.into(), &input) {
+        Ok(output) => output,
+        Err(err) => err.write_errors().into(),
+    }
+}
+
+fn compact_inner(
+    args: proc_macro2::TokenStream,
+    input: &syn::DeriveInput,
+) -> Result<TokenStream, Error> {
+    validate_compact(input)?;
+
+    Ok(gen_compact(args, input))
+}
+
+#[proc_macro_derive(Aligned, attributes(aligned))]
+pub fn aligned(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match newtype_inner(&input, gen_aligned) {
+        Ok(output) => output,
+        Err(err) => err.write_errors().into(),
+    }
+}
+
+#[proc_macro_derive(FieldDeref)]
+pub fn field_deref(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match newtype_inner(&input, gen_field_deref) {
+        Ok(output) => output,
+        Err(err) => err.write_errors().into(),
+    }
+}
+
+#[proc_macro_derive(FieldDerefMut)]
+pub fn field_deref_mut(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match newtype_inner(&input, gen_field_deref_mut) {
+        Ok(output) => output,
+        Err(err) => err.write_errors().into(),
+    }
+}
+
+fn newtype_inner(
+    input: &syn::DeriveInput,
+    gen: fn(&NewtypeInput) -> TokenStream,
+) -> Result<TokenStream, Error> {
+    let input = NewtypeInput::from_derive_input(input)?;
+
+    validate_newtype(&input)?;
+
+    Ok(gen(&input))
+}
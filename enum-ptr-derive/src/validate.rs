@@ -1,6 +1,6 @@
 use darling::{ast, Error};
 
-use crate::Input;
+use crate::{Input, NewtypeInput};
 
 pub fn validate_input(input: &Input) -> Result<(), Error> {
     let mut errors = Error::accumulator();
@@ -9,6 +9,11 @@ pub fn validate_input(input: &Input) -> Result<(), Error> {
         errors.push(Error::custom("missing `#[repr(C, usize)]`"))
     }
 
+    if input.copy.is_present() && input.iterative_drop.is_present() {
+        let msg = "`copy` and `iterative_drop` are mutually exclusive";
+        errors.push(Error::custom(msg).with_span(&input.iterative_drop.span()))
+    }
+
     let ast::Data::Enum(variants) = &input.data else { unreachable!() };
     for variant in variants {
         if variant.fields.len() != 1 {
@@ -22,3 +27,38 @@ pub fn validate_input(input: &Input) -> Result<(), Error> {
 
     errors.finish()
 }
+
+pub fn validate_compact(input: &syn::DeriveInput) -> Result<(), Error> {
+    let mut errors = Error::accumulator();
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("repr") {
+            let msg = "`#[compact]` already adds `#[repr(C, usize)]`";
+            errors.push(Error::custom(msg).with_span(attr));
+        }
+    }
+
+    errors.finish()
+}
+
+pub fn validate_newtype(input: &NewtypeInput) -> Result<(), Error> {
+    let mut errors = Error::accumulator();
+
+    if !input.attrs.contains(&syn::parse_quote!(#[repr(transparent)])) {
+        errors.push(Error::custom("missing `#[repr(transparent)]`"))
+    }
+
+    let ast::Data::Struct(fields) = &input.data else { unreachable!() };
+    if fields.len() != 1 {
+        errors.push(Error::custom("expect exactly one field").with_span(&input.ident));
+    }
+
+    if let Some(min) = &input.min {
+        match min.base10_parse::<usize>() {
+            Ok(min) if min.is_power_of_two() => {}
+            _ => errors.push(Error::custom("expect a power of two").with_span(min)),
+        }
+    }
+
+    errors.finish()
+}
@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+
+use enum_ptr::{Compact, EnumPtr};
+
+#[derive(EnumPtr)]
+#[repr(C, usize)]
+enum Foo<'a> {
+    A(&'a u64),
+    B(&'a u8),
+}
+
+#[derive(EnumPtr)]
+#[repr(C, usize)]
+enum Bar<T> {
+    A(T),
+    B(Box<T>),
+}
+
+fn main() {
+    let _ = Compact::from(Bar::<String>::B(Box::new(String::new())));
+}
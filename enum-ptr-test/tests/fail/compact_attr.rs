@@ -0,0 +1,12 @@
+#![allow(dead_code)]
+
+use enum_ptr::compact;
+
+#[compact]
+#[repr(C)]
+enum Foo<'a> {
+    A(&'a i64),
+    B(&'a u64),
+}
+
+fn main() {}
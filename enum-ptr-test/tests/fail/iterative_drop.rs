@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+
+use enum_ptr::EnumPtr;
+
+#[derive(EnumPtr, Clone, Copy)]
+#[enum_ptr(copy, iterative_drop)]
+#[repr(C, usize)]
+enum Foo<'a> {
+    A(&'a i64),
+    B(&'a u64),
+}
+
+#[derive(EnumPtr)]
+#[enum_ptr(iterative_drop)]
+#[repr(C, usize)]
+enum Bar {
+    A(Box<i64>),
+    B(Box<u64>),
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+
+use enum_ptr::{Compact, CompactRef, EnumPtr};
+
+#[derive(EnumPtr)]
+#[enum_ptr(borrow)]
+#[repr(C, usize)]
+enum Foo<'a, 'b> {
+    A(Option<&'a mut i32>),
+    B(Option<&'b mut i32>),
+}
+
+fn test_new() {
+    let foo: Compact<_> = Foo::A(None).into();
+    let foo_ref = CompactRef::new(&foo);
+    drop(foo);
+    drop(foo_ref);
+}
+
+fn test_borrow() {
+    let foo: Compact<_> = Foo::A(None).into();
+    let foo_ref = CompactRef::new(&foo).borrow();
+    drop(foo);
+    drop(foo_ref);
+}
+
+fn main() {}
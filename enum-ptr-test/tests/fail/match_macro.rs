@@ -0,0 +1,18 @@
+#![allow(dead_code)]
+
+use enum_ptr::{Compact, EnumPtr};
+
+#[derive(EnumPtr)]
+#[enum_ptr(match_macro)]
+#[repr(C, usize)]
+enum Foo<'a, T> {
+    A(&'a T),
+    B(Box<T>),
+}
+
+fn main() {
+    let foo: Compact<_> = Foo::A(&1u64).into();
+    let _ = match_foo!(foo, {
+        A(a) => *a,
+    });
+}
@@ -0,0 +1,25 @@
+#![allow(dead_code)]
+
+use enum_ptr::{Aligned, FieldDeref};
+
+#[derive(Aligned)]
+struct Foo<'a>(&'a i32);
+
+#[derive(FieldDeref)]
+#[repr(transparent)]
+struct Bar<'a> {
+    a: &'a i32,
+    b: &'a i32,
+}
+
+#[derive(Aligned)]
+#[aligned(min = 3)]
+#[repr(transparent)]
+struct Baz<'a>(&'a i32);
+
+#[derive(Aligned)]
+#[aligned(min = 8)]
+#[repr(transparent)]
+struct Qux<'a>(&'a u16);
+
+fn main() {}
@@ -0,0 +1,14 @@
+#![allow(dead_code)]
+
+use enum_ptr::{EnumPtr, ShiftUsize};
+
+#[derive(EnumPtr)]
+#[enum_ptr(relocatable)]
+#[repr(C, usize)]
+enum Foo<'a> {
+    A(&'a i32),
+    B(Option<&'a i32>),
+    C(ShiftUsize<2>),
+}
+
+fn main() {}
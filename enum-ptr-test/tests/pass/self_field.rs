@@ -0,0 +1,26 @@
+#![allow(dead_code)]
+
+use enum_ptr::{EnumPtr, Unit};
+
+#[derive(EnumPtr)]
+#[repr(C, usize)]
+enum Tree {
+    Node(Box<(i32, Self)>),
+    Leaf(Unit),
+}
+
+#[derive(EnumPtr)]
+#[repr(C, usize)]
+enum List<'a> {
+    Cons(&'a (i32, Self)),
+    Nil(Unit),
+}
+
+#[derive(EnumPtr)]
+#[repr(C, usize)]
+enum Generic<T> {
+    Node(Box<(T, Self)>),
+    Leaf(Unit),
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+
+use std::fmt::Debug;
+
+use enum_ptr::EnumPtr;
+
+#[derive(EnumPtr)]
+#[enum_ptr(as_dyn(Debug))]
+#[repr(C, usize)]
+enum Pair<'a, 'b>
+where
+    'b: 'a,
+{
+    First(&'a i64),
+    Second(&'b i64),
+}
+
+#[derive(EnumPtr)]
+#[enum_ptr(as_dyn_mut(Debug))]
+#[repr(C, usize)]
+enum Owned<T>
+where
+    T: Debug,
+{
+    One(Box<T>),
+    Two(Box<[T; 2]>),
+}
+
+fn main() {}
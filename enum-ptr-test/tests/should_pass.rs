@@ -0,0 +1,6 @@
+#[test]
+#[cfg_attr(miri, ignore)]
+fn should_pass() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/pass/*.rs");
+}
@@ -0,0 +1,230 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use crate::{Compact, Compactable};
+
+/// Atomic slot of [`Compact<T>`] that hands out clones safely, like
+/// `arc-swap`. Meant for enums whose variants are `Arc`, `Option<Arc>` or
+/// inline values.
+///
+/// Loading clones the current value (e.g., increments the reference count)
+/// while registered in one of two reader counts, selected by an epoch. After
+/// replacing a value, a writer flips the epoch twice and waits for each count
+/// to drain, so the old value is only handed back after every reader that may
+/// have seen it has left. Readers that arrive later register in the other
+/// count, so a steady stream of them cannot starve writers. It works without
+/// `std`, at the cost of writers spinning while earlier readers are cloning.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use std::sync::Arc;
+///
+/// use enum_ptr::{ArcCompactSwap, EnumPtr, Unit};
+///
+/// #[derive(EnumPtr, Clone, Debug, PartialEq, Eq)]
+/// #[enum_ptr(borrow)]
+/// #[repr(C, usize)]
+/// enum Config {
+///     Custom(Arc<String>),
+///     #[enum_ptr(skip)]
+///     Default(Unit),
+/// }
+///
+/// let config = ArcCompactSwap::new(Config::Default(Unit::new()).into());
+/// std::thread::scope(|s| {
+///     s.spawn(|| config.store(Config::Custom(Arc::new("a".into())).into()));
+///     s.spawn(|| {
+///         let guard = config.load();
+///         if let ConfigRef::Custom(s) = guard.borrow() {
+///             assert_eq!(s.as_str(), "a");
+///         }
+///     });
+/// });
+///
+/// config.rcu(|old| match old {
+///     Config::Custom(s) => Config::Custom(Arc::new(format!("{s}b"))),
+///     Config::Default(_) => unreachable!(),
+/// });
+/// assert_eq!(config.load_full(), Config::Custom(Arc::new("ab".into())));
+/// # }
+/// ```
+pub struct ArcCompactSwap<T: Compactable + Clone> {
+    data: AtomicPtr<u8>,
+    epoch: AtomicUsize,
+    readers: [AtomicUsize; 2],
+    writer: AtomicBool,
+    marker: PhantomData<UnsafeCell<Compact<T>>>,
+}
+
+impl<T: Compactable + Clone> ArcCompactSwap<T> {
+    /// Creates a new slot holding `value`.
+    #[inline]
+    pub fn new(value: Compact<T>) -> Self {
+        Self {
+            data: AtomicPtr::new(value.into_raw() as *mut u8),
+            epoch: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            writer: AtomicBool::new(false),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a guard holding a clone of the current value.
+    #[inline]
+    pub fn load(&self) -> ArcCompactGuard<T> {
+        ArcCompactGuard {
+            value: self.load_full().compact(),
+        }
+    }
+
+    /// Returns a clone of the current value.
+    #[inline]
+    pub fn load_full(&self) -> T {
+        self.load_word().1
+    }
+
+    /// Stores `value`, and drops the old one once no reader is using it.
+    #[inline]
+    pub fn store(&self, value: Compact<T>) {
+        drop(self.swap(value));
+    }
+
+    /// Stores `value`, and returns the old one once no reader is using it.
+    pub fn swap(&self, value: Compact<T>) -> Compact<T> {
+        let word = self
+            .data
+            .swap(value.into_raw() as *mut u8, Ordering::SeqCst);
+        self.synchronize();
+        unsafe { Compact::from_raw(word as *mut ()) }
+    }
+
+    /// Replaces the value with the result of `f` applied to the current one,
+    /// retrying if it has been changed concurrently. Returns the old value.
+    ///
+    /// `f` may be called multiple times. The change is detected by comparing
+    /// the stored raw data, so it is only reliable for variants whose clones
+    /// keep the payload alive, such as `Arc`, `Option<Arc>` or inline values.
+    /// Otherwise a freed address may be reused and a concurrent update lost.
+    pub fn rcu(&self, mut f: impl FnMut(&T) -> T) -> Compact<T> {
+        loop {
+            let (current_word, current) = self.load_word();
+            let new_word = f(&current).compact().into_raw() as *mut u8;
+            match self.data.compare_exchange(
+                current_word,
+                new_word,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(word) => {
+                    self.synchronize();
+                    return unsafe { Compact::from_raw(word as *mut ()) };
+                }
+                Err(_) => drop(unsafe { Compact::<T>::from_raw(new_word as *mut ()) }),
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Compact<T> {
+        unsafe { &mut *(self.data.get_mut() as *mut *mut u8 as *mut Compact<T>) }
+    }
+
+    /// Consumes the slot and returns the value.
+    #[inline]
+    pub fn into_inner(self) -> Compact<T> {
+        let this = core::mem::ManuallyDrop::new(self);
+        unsafe { Compact::from_raw(this.data.load(Ordering::Relaxed) as *mut ()) }
+    }
+
+    /// Returns the stored word and a clone of the value it encodes.
+    fn load_word(&self) -> (*mut u8, T) {
+        let readers = &self.readers[self.epoch.load(Ordering::SeqCst) & 1];
+        readers.fetch_add(1, Ordering::SeqCst);
+        let _reader = Reader(readers);
+        let word = self.data.load(Ordering::SeqCst);
+        let value = unsafe { Compact::<T>::ref_from_raw(word as *const ()) }.map_ref(T::clone);
+        (word, value)
+    }
+
+    /// Waits until every reader that started before the call has left.
+    fn synchronize(&self) {
+        while self
+            .writer
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        // Readers may have registered in either count, so drain both. New
+        // readers register in the other count than the one being drained.
+        for _ in 0..2 {
+            let readers = &self.readers[self.epoch.fetch_add(1, Ordering::SeqCst) & 1];
+            while readers.load(Ordering::SeqCst) != 0 {
+                core::hint::spin_loop();
+            }
+        }
+        self.writer.store(false, Ordering::Release);
+    }
+}
+
+impl<T: Compactable + Clone> Drop for ArcCompactSwap<T> {
+    #[inline]
+    fn drop(&mut self) {
+        drop(unsafe { Compact::<T>::from_raw(*self.data.get_mut() as *mut ()) });
+    }
+}
+
+impl<T: Compactable + Clone + core::fmt::Debug> core::fmt::Debug for ArcCompactSwap<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ArcCompactSwap")
+            .field(&*self.load())
+            .finish()
+    }
+}
+
+unsafe impl<T: Compactable + Clone + Send + Sync> Sync for ArcCompactSwap<T> {}
+
+/// Leaves the reader count when dropped, even if cloning panics.
+struct Reader<'a>(&'a AtomicUsize);
+
+impl Drop for Reader<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// Guard returned by [`ArcCompactSwap::load`]. It dereferences to
+/// [`Compact<T>`].
+pub struct ArcCompactGuard<T: Compactable> {
+    value: Compact<T>,
+}
+
+impl<T: Compactable> ArcCompactGuard<T> {
+    /// Returns the loaded value.
+    #[inline]
+    pub fn into_inner(self) -> Compact<T> {
+        self.value
+    }
+}
+
+impl<T: Compactable> Deref for ArcCompactGuard<T> {
+    type Target = Compact<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Compactable + core::fmt::Debug> core::fmt::Debug for ArcCompactGuard<T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.value.fmt(f)
+    }
+}
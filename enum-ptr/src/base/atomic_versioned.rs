@@ -0,0 +1,248 @@
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::{Compact, CompactInnerCopy, Compactable};
+
+/// Number of low bits that are zeros in every compact value, including the
+/// tag.
+pub(crate) const fn low_bits<T: Compactable>() -> u32 {
+    let mut bits = usize::BITS;
+    let mut i = 0;
+    while i < T::VARIANT_ALIGNMENTS.len() {
+        let align = T::VARIANT_ALIGNMENTS[i];
+        // Non-power-of-two alignments (e.g., `Unit`) do not restrict bits.
+        if align.is_power_of_two() && align.trailing_zeros() < bits {
+            bits = align.trailing_zeros();
+        }
+        i += 1;
+    }
+    bits
+}
+
+/// Atomic [`Compact<T>`] with a wrapping version counter in its spare bits,
+/// to avoid the ABA problem in CAS loops. Only one-pointer wide.
+///
+/// By default, the counter lives in the low bits that the tag leaves free,
+/// which is usually only a few bits. If `HIGH_BITS` is not zero, it lives in
+/// the top `HIGH_BITS` bits instead, which must be zeros in every stored value
+/// (e.g., 16 on common 64-bit platforms). Storing a value that overlaps the
+/// counter panics.
+///
+/// Every successful write bumps the counter, and
+/// [`compare_exchange`](Self::compare_exchange) compares it along with the
+/// value. Only `copy` enums are supported, since values are loaded by copy.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(target_pointer_width = "64")] {
+/// use core::sync::atomic::Ordering::SeqCst;
+///
+/// use enum_ptr::{AtomicVersionedCompact, EnumPtr};
+///
+/// #[derive(EnumPtr, Clone, Copy)]
+/// #[enum_ptr(copy)]
+/// #[repr(C, usize)]
+/// enum Head<'a> {
+///     Node(&'a u64),
+///     Empty(Option<&'a u64>),
+/// }
+///
+/// let node = 1;
+/// let head = AtomicVersionedCompact::<Head, 16>::new(Head::Node(&node).into());
+/// let (seen, version) = head.load_versioned(SeqCst);
+///
+/// // Another thread pops and pushes the same node back.
+/// head.store(Head::Empty(None).into(), SeqCst);
+/// head.store(Head::Node(&node).into(), SeqCst);
+///
+/// let empty = Head::Empty(None).into();
+/// assert!(head.compare_exchange(seen, version, empty, SeqCst, SeqCst).is_err());
+///
+/// let (seen, version) = head.load_versioned(SeqCst);
+/// assert_eq!(version, 2);
+/// assert!(head.compare_exchange(seen, version, empty, SeqCst, SeqCst).is_ok());
+/// # }
+/// ```
+pub struct AtomicVersionedCompact<
+    T: Compactable<Inner = CompactInnerCopy<T>> + Copy,
+    const HIGH_BITS: u32 = 0,
+> {
+    data: AtomicPtr<u8>,
+    marker: PhantomData<Compact<T>>,
+}
+
+impl<T: Compactable<Inner = CompactInnerCopy<T>> + Copy, const HIGH_BITS: u32>
+    AtomicVersionedCompact<T, HIGH_BITS>
+{
+    /// Number of bits of the version counter.
+    pub const VERSION_BITS: u32 = {
+        let bits = match HIGH_BITS {
+            0 => low_bits::<T>() - T::TAG_BITS,
+            _ => HIGH_BITS,
+        };
+        assert!(
+            bits > 0 && bits < usize::BITS,
+            "no spare bits for the version"
+        );
+        bits
+    };
+
+    const VERSION_SHIFT: u32 = match HIGH_BITS {
+        0 => T::TAG_BITS,
+        _ => usize::BITS - HIGH_BITS,
+    };
+
+    const VERSION_MASK: usize = ((1 << Self::VERSION_BITS) - 1) << Self::VERSION_SHIFT;
+
+    /// Creates a new atomic value with version `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` overlaps the version bits.
+    #[inline]
+    pub fn new(value: Compact<T>) -> Self {
+        Self {
+            data: AtomicPtr::new(Self::pack(value, 0)),
+            marker: PhantomData,
+        }
+    }
+
+    /// Loads the value.
+    #[inline]
+    pub fn load(&self, order: Ordering) -> Compact<T> {
+        self.load_versioned(order).0
+    }
+
+    /// Loads the value and its version.
+    #[inline]
+    pub fn load_versioned(&self, order: Ordering) -> (Compact<T>, usize) {
+        Self::unpack(self.data.load(order))
+    }
+
+    /// Stores `value` and bumps the version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` overlaps the version bits.
+    #[inline]
+    pub fn store(&self, value: Compact<T>, order: Ordering) {
+        self.swap(value, order);
+    }
+
+    /// Stores `value` and bumps the version, returning the previous value and
+    /// version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` overlaps the version bits.
+    pub fn swap(&self, value: Compact<T>, order: Ordering) -> (Compact<T>, usize) {
+        let mut current = self.data.load(Ordering::Relaxed);
+        loop {
+            let (_, version) = Self::unpack(current);
+            let new = Self::pack(value, version.wrapping_add(1));
+            match self
+                .data
+                .compare_exchange_weak(current, new, order, Ordering::Relaxed)
+            {
+                Ok(word) => return Self::unpack(word),
+                Err(word) => current = word,
+            }
+        }
+    }
+
+    /// Stores `new` with the next version if the current value and version
+    /// are `current` and `version`. Returns the previous value and version in
+    /// either case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `current` or `new` overlaps the version bits.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: Compact<T>,
+        version: usize,
+        new: Compact<T>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<(Compact<T>, usize), (Compact<T>, usize)> {
+        self.data
+            .compare_exchange(
+                Self::pack(current, version),
+                Self::pack(new, version.wrapping_add(1)),
+                success,
+                failure,
+            )
+            .map(Self::unpack)
+            .map_err(Self::unpack)
+    }
+
+    /// Like [`compare_exchange`](Self::compare_exchange), but may fail
+    /// spuriously.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `current` or `new` overlaps the version bits.
+    #[inline]
+    pub fn compare_exchange_weak(
+        &self,
+        current: Compact<T>,
+        version: usize,
+        new: Compact<T>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<(Compact<T>, usize), (Compact<T>, usize)> {
+        self.data
+            .compare_exchange_weak(
+                Self::pack(current, version),
+                Self::pack(new, version.wrapping_add(1)),
+                success,
+                failure,
+            )
+            .map(Self::unpack)
+            .map_err(Self::unpack)
+    }
+
+    /// Consumes the atomic value and returns the value and its version.
+    #[inline]
+    pub fn into_inner(self) -> (Compact<T>, usize) {
+        Self::unpack(self.data.into_inner())
+    }
+
+    #[inline]
+    fn pack(value: Compact<T>, version: usize) -> *mut u8 {
+        let word = value.into_raw() as *mut u8;
+        assert!(
+            word as usize & Self::VERSION_MASK == 0,
+            "value overlaps the version bits"
+        );
+        word.wrapping_add((version << Self::VERSION_SHIFT) & Self::VERSION_MASK)
+    }
+
+    #[inline]
+    fn unpack(word: *mut u8) -> (Compact<T>, usize) {
+        let bits = word as usize & Self::VERSION_MASK;
+        let value = unsafe { Compact::from_raw(word.wrapping_sub(bits) as *mut ()) };
+        (value, bits >> Self::VERSION_SHIFT)
+    }
+}
+
+impl<
+        T: Compactable<Inner = CompactInnerCopy<T>> + Copy + core::fmt::Debug,
+        const HIGH_BITS: u32,
+    > core::fmt::Debug for AtomicVersionedCompact<T, HIGH_BITS>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (value, version) = self.load_versioned(Ordering::Relaxed);
+        f.debug_struct("AtomicVersionedCompact")
+            .field("value", &value)
+            .field("version", &version)
+            .finish()
+    }
+}
+
+unsafe impl<T: Compactable<Inner = CompactInnerCopy<T>> + Copy + Send + Sync, const HIGH_BITS: u32>
+    Sync for AtomicVersionedCompact<T, HIGH_BITS>
+{
+}
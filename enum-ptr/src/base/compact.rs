@@ -1,6 +1,9 @@
 use core::mem::{transmute_copy, ManuallyDrop};
 
-use crate::{CompactBorrow, CompactBorrowMut, CompactInnerCopy, Compactable};
+use crate::{
+    CompactAsDyn, CompactAsDynMut, CompactBorrow, CompactBorrowMut, CompactInnerCopy, CompactRef,
+    Compactable,
+};
 
 /// Compact representation of `T`. Only one-pointer wide.
 ///
@@ -17,12 +20,162 @@ impl<T: Compactable> Compact<T> {
         unsafe { transmute_copy(self) }
     }
 
+    /// Returns the tag, i.e., the index of the current variant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use enum_ptr::{Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[repr(C, usize)]
+    /// enum Foo<'a> {
+    ///     A(&'a i32),
+    ///     B(&'a u32),
+    /// }
+    ///
+    /// let foo: Compact<_> = Foo::B(&1).into();
+    /// assert_eq!(foo.tag(), 1);
+    /// ```
+    #[inline]
+    pub fn tag(&self) -> usize {
+        self.as_raw_data() as usize & T::MASK
+    }
+
+    /// Returns the name of the current variant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use enum_ptr::{Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[repr(C, usize)]
+    /// enum Foo<'a> {
+    ///     A(&'a i32),
+    ///     B(&'a u32),
+    /// }
+    ///
+    /// let foo: Compact<_> = Foo::B(&1).into();
+    /// assert_eq!(foo.variant_name(), "B");
+    /// ```
+    #[inline]
+    pub fn variant_name(&self) -> &'static str {
+        T::VARIANT_NAMES[self.tag()]
+    }
+
+    /// Returns the address of the payload, i.e., the raw data without the tag.
+    #[inline]
+    pub fn addr(&self) -> usize {
+        self.as_raw_data() as usize & !T::MASK
+    }
+
+    /// Returns whether two compact values are the same variant with the same
+    /// payload address, without comparing the pointees.
+    ///
+    /// See also [`ByAddress`](crate::ByAddress).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use std::rc::Rc;
+    ///
+    /// use enum_ptr::{Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr, Clone, PartialEq)]
+    /// #[repr(C, usize)]
+    /// enum Foo {
+    ///     A(Rc<i32>),
+    ///     B(Rc<u32>),
+    /// }
+    ///
+    /// let a: Compact<_> = Foo::A(Rc::new(1)).into();
+    /// let b = a.clone();
+    /// let c: Compact<_> = Foo::A(Rc::new(1)).into();
+    /// assert!(Compact::ptr_eq(&a, &b));
+    /// assert!(!Compact::ptr_eq(&a, &c));
+    /// assert!(a == c);
+    /// assert_eq!(a.addr(), b.addr());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.as_raw_data() == other.as_raw_data()
+    }
+
     /// Returns the original value.
     #[inline]
     pub fn extract(self) -> T {
         T::extract(self)
     }
 
+    /// Consumes the compact value and returns the underlying raw data, e.g.,
+    /// to store it in a `void *` slot of C APIs.
+    ///
+    /// The returned pointer carries the provenance of the payload. Keep it as
+    /// a pointer when passing it around. If it must be turned into an integer,
+    /// use `expose_provenance` and [`core::ptr::with_exposed_provenance_mut`]
+    /// instead of `as` casts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use enum_ptr::{get_ref, Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr, Debug, PartialEq, Eq)]
+    /// #[repr(C, usize)]
+    /// enum Foo {
+    ///     A(Box<i32>),
+    ///     B(Box<u32>),
+    /// }
+    ///
+    /// let foo: Compact<_> = Foo::B(Box::new(1)).into();
+    /// let raw = foo.into_raw();
+    ///
+    /// let foo_ref = unsafe { Compact::<Foo>::ref_from_raw(raw) };
+    /// assert_eq!(get_ref!(foo_ref, Foo::B), Some(&1));
+    ///
+    /// let foo = unsafe { Compact::<Foo>::from_raw(raw) };
+    /// assert_eq!(foo.extract(), Foo::B(Box::new(1)));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn into_raw(self) -> *mut () {
+        let this = ManuallyDrop::new(self);
+        this.as_raw_data() as *mut ()
+    }
+
+    /// Constructs a compact value from raw data returned by
+    /// [`into_raw`](Self::into_raw).
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be returned by [`into_raw`](Self::into_raw) of the same
+    /// type, with its provenance preserved, and must be consumed only once.
+    #[inline]
+    pub unsafe fn from_raw(raw: *mut ()) -> Self {
+        transmute_copy(&(raw as *const u8))
+    }
+
+    /// Borrows a compact value from raw data returned by
+    /// [`into_raw`](Self::into_raw) without taking its ownership.
+    ///
+    /// The raw data is the compact value itself rather than a pointer to it,
+    /// so a [`CompactRef`] is returned instead of `&'a Compact<T>`. It
+    /// dereferences to [`Compact<T>`].
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be returned by [`into_raw`](Self::into_raw) of the same
+    /// type, with its provenance preserved, and must not be consumed by
+    /// [`from_raw`](Self::from_raw) during `'a`.
+    #[inline]
+    pub unsafe fn ref_from_raw<'a>(raw: *const ()) -> CompactRef<'a, T> {
+        transmute_copy(&(raw as *const u8))
+    }
+
     #[inline]
     unsafe fn temp_extract(&self) -> ManuallyDrop<T> {
         ManuallyDrop::new(T::extract(transmute_copy(self)))
@@ -129,6 +282,123 @@ impl<T: Compactable> Compact<T> {
     // }
 }
 
+// `T` is a `#[repr(C, usize)]` enum with one pointer-sized field per variant,
+// so it is exactly two `Compact<T>`s wide, with the same alignment. This lets
+// the conversions below work in the same buffer.
+impl<T: Compactable> Compact<T> {
+    /// Converts a vector of values into compact values, reusing its
+    /// allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use enum_ptr::{Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr, Debug, PartialEq, Eq)]
+    /// #[repr(C, usize)]
+    /// enum Foo {
+    ///     A(Box<i32>),
+    ///     B(Box<u32>),
+    /// }
+    ///
+    /// let vec = vec![Foo::A(Box::new(1)), Foo::B(Box::new(2))];
+    /// let ptr = vec.as_ptr() as *const u8;
+    /// let compact_vec = Compact::compact_vec(vec);
+    /// assert_eq!(compact_vec.as_ptr() as *const u8, ptr);
+    ///
+    /// let vec = Compact::extract_vec(compact_vec);
+    /// assert_eq!(vec, [Foo::A(Box::new(1)), Foo::B(Box::new(2))]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn compact_vec(vec: alloc::vec::Vec<T>) -> alloc::vec::Vec<Self> {
+        let mut vec = ManuallyDrop::new(vec);
+        let (ptr, len, cap) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+        let compact_ptr = ptr as *mut Self;
+        unsafe {
+            // Slot `i` of the result only overlaps values before `i`, which
+            // have already been moved out.
+            for i in 0..len {
+                compact_ptr.add(i).write(ptr.add(i).read().compact());
+            }
+            alloc::vec::Vec::from_raw_parts(compact_ptr, len, cap * 2)
+        }
+    }
+
+    /// Converts a vector of compact values back, reusing its allocation if
+    /// possible.
+    ///
+    /// The allocation is grown to twice the length at most, which is the
+    /// least required by the result.
+    #[cfg(feature = "alloc")]
+    pub fn extract_vec(mut vec: alloc::vec::Vec<Self>) -> alloc::vec::Vec<T> {
+        vec.reserve_exact(vec.len());
+        if vec.capacity() & 1 != 0 {
+            return vec.into_iter().map(Self::extract).collect();
+        }
+        let mut vec = ManuallyDrop::new(vec);
+        let (ptr, len, cap) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+        let value_ptr = ptr as *mut T;
+        unsafe {
+            // Slot `i` of the result only overlaps values after `i`, which
+            // have already been moved out.
+            for i in (0..len).rev() {
+                value_ptr.add(i).write(ptr.add(i).read().extract());
+            }
+            alloc::vec::Vec::from_raw_parts(value_ptr, len, cap / 2)
+        }
+    }
+
+    /// Temporarily converts a slice of values into compact values in place,
+    /// and passes them to `f`. They are converted back afterwards, even if `f`
+    /// panics.
+    ///
+    /// The compact values occupy the first half of the memory of `slice`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use enum_ptr::{Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr, Debug, PartialEq, Eq)]
+    /// #[repr(C, usize)]
+    /// enum Foo {
+    ///     A(Box<i32>),
+    ///     B(Box<u32>),
+    /// }
+    ///
+    /// let mut slice = [Foo::A(Box::new(1)), Foo::B(Box::new(2))];
+    /// Compact::with_compact_slice(&mut slice, |compacts| compacts.swap(0, 1));
+    /// assert_eq!(slice, [Foo::B(Box::new(2)), Foo::A(Box::new(1))]);
+    /// # }
+    /// ```
+    pub fn with_compact_slice<R>(slice: &mut [T], f: impl FnOnce(&mut [Self]) -> R) -> R {
+        struct Restore<T: Compactable> {
+            ptr: *mut T,
+            len: usize,
+        }
+
+        impl<T: Compactable> Drop for Restore<T> {
+            fn drop(&mut self) {
+                let compact_ptr = self.ptr as *mut Compact<T>;
+                for i in (0..self.len).rev() {
+                    unsafe { self.ptr.add(i).write(compact_ptr.add(i).read().extract()) }
+                }
+            }
+        }
+
+        let (ptr, len) = (slice.as_mut_ptr(), slice.len());
+        let compact_ptr = ptr as *mut Self;
+        for i in 0..len {
+            unsafe { compact_ptr.add(i).write(ptr.add(i).read().compact()) }
+        }
+        let _restore = Restore { ptr, len };
+        f(unsafe { core::slice::from_raw_parts_mut(compact_ptr, len) })
+    }
+}
+
 impl<T: CompactBorrow> Compact<T> {
     /// Returns a reference type that acts like `&T`.
     ///
@@ -193,6 +463,78 @@ impl<T: CompactBorrowMut> Compact<T> {
     }
 }
 
+impl<T: Compactable> Compact<T> {
+    /// Returns a trait object that points to the object of the current
+    /// variant.
+    ///
+    /// Check [`EnumPtr`](crate::EnumPtr) for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use core::fmt::Display;
+    ///
+    /// use enum_ptr::{Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[enum_ptr(as_dyn(Display))] // required
+    /// #[repr(C, usize)]
+    /// enum Foo {
+    ///     A(Box<i32>),
+    ///     B(Box<u32>),
+    /// }
+    ///
+    /// let foo: Compact<_> = Foo::A(Box::new(1)).into();
+    /// let display: &dyn Display = foo.as_dyn();
+    /// assert_eq!(display.to_string(), "1");
+    /// # }
+    /// ```
+    #[inline]
+    pub fn as_dyn<D: ?Sized>(&self) -> &D
+    where
+        T: CompactAsDyn<D>,
+    {
+        T::as_dyn(self)
+    }
+
+    /// Returns a mutable trait object that points to the object of the
+    /// current variant.
+    ///
+    /// Check [`EnumPtr`](crate::EnumPtr) for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use core::fmt::Write;
+    ///
+    /// use enum_ptr::{Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[enum_ptr(as_dyn_mut(Write))] // required
+    /// #[repr(C, usize)]
+    /// enum Foo {
+    ///     A(Box<String>),
+    ///     B(Box<String>),
+    /// }
+    ///
+    /// let mut foo: Compact<_> = Foo::A(Box::new(String::new())).into();
+    /// let writer: &mut dyn Write = foo.as_dyn_mut();
+    /// write!(writer, "{}", 1).unwrap();
+    /// assert_eq!(foo.as_dyn_mut::<dyn Write>().write_str("2"), Ok(()));
+    /// assert!(matches!(foo.extract(), Foo::A(s) if *s == "12"));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn as_dyn_mut<D: ?Sized>(&mut self) -> &mut D
+    where
+        T: CompactAsDynMut<D>,
+    {
+        T::as_dyn_mut(self)
+    }
+}
+
 impl<T: Compactable> Clone for Compact<T>
 where
     T::Inner: Clone,
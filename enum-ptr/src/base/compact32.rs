@@ -0,0 +1,147 @@
+use core::marker::PhantomData;
+use core::mem::{transmute, transmute_copy, ManuallyDrop};
+
+use crate::{Arena, Compact, CompactBorrow, CompactRelocatable};
+
+/// Compact representation of `T` relative to an [`Arena`]. Only 32-bit wide.
+///
+/// Addresses are stored as offsets from the start of the arena, while other
+/// values (e.g., [`Unit`](crate::Unit)) are stored as they are. Both of them
+/// share the low bits with the tag, just like [`Compact`]. Therefore, the same
+/// enum can be stored by either [`Compact`] or [`Compact32`].
+///
+/// It requires `#[enum_ptr(relocatable)]`, and all fields to implement
+/// [`Relocatable`](crate::Relocatable).
+///
+/// # Examples
+///
+/// ```
+/// use enum_ptr::{Arena, Compact32, EnumPtr, Unit};
+///
+/// #[derive(EnumPtr, Debug, Clone, Copy, PartialEq, Eq)]
+/// #[enum_ptr(relocatable, borrow)]
+/// #[repr(C, usize)]
+/// enum Node<'a> {
+///     Leaf(&'a i64),
+///     Pair(&'a (i64, i64)),
+///     #[enum_ptr(skip)]
+///     Empty(Unit),
+/// }
+///
+/// let arena = Arena::with_capacity(1024);
+/// let leaf = Compact32::new(Node::Leaf(arena.alloc(1)), &arena);
+/// let empty = Compact32::new(Node::Empty(Unit::new()), &arena);
+/// assert_eq!(core::mem::size_of_val(&leaf), 4);
+///
+/// unsafe {
+///     assert!(matches!(leaf.resolve(&arena), NodeRef::Leaf(&1)));
+///     assert_eq!(empty.extract(&arena), Node::Empty(Unit::new()));
+/// }
+///
+/// // Addresses outside of the arena are rejected.
+/// assert!(Compact32::try_new(Node::Leaf(&1), &arena).is_err());
+///
+/// // So are values that do not lie in the used region.
+/// let half: &(i64, i64) = unsafe { &*(arena.alloc(2i64) as *const i64).cast() };
+/// assert!(Compact32::try_new(Node::Pair(half), &arena).is_err());
+/// ```
+#[repr(transparent)]
+pub struct Compact32<T: CompactRelocatable> {
+    data: u32,
+    marker: PhantomData<T>,
+}
+
+impl<T: CompactRelocatable> Compact32<T> {
+    /// Creates a compact value relative to `arena`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an address is out of `arena`, or a value does not fit in 32
+    /// bits.
+    #[inline]
+    pub fn new(value: T, arena: &Arena) -> Self {
+        match Self::try_new(value, arena) {
+            Ok(compact) => compact,
+            Err(_) => panic!("value cannot be stored relative to the arena"),
+        }
+    }
+
+    /// Creates a compact value relative to `arena`, or returns the value back
+    /// if an address is out of `arena`, or a value does not fit in 32 bits.
+    pub fn try_new(value: T, arena: &Arena) -> Result<Self, T> {
+        let compact = ManuallyDrop::new(value.compact());
+        let tag = compact.tag();
+        let ptr = compact.as_raw_data().wrapping_sub(tag);
+        let payload = match T::TARGETS[tag] {
+            Some(layout) => arena.offset_of(ptr, layout.size()),
+            None => Some(ptr as usize),
+        };
+        let payload = payload.filter(|payload| payload & T::MASK == 0);
+        match payload.and_then(|payload| u32::try_from(payload | tag).ok()) {
+            Some(data) => Ok(Self {
+                data,
+                marker: PhantomData,
+            }),
+            None => Err(ManuallyDrop::into_inner(compact).extract()),
+        }
+    }
+
+    /// Returns the underlying raw data.
+    #[inline]
+    pub fn as_raw_data(&self) -> u32 {
+        self.data
+    }
+
+    /// Returns the tag, i.e., the index of the current variant.
+    #[inline]
+    pub fn tag(&self) -> usize {
+        self.data as usize & T::MASK
+    }
+
+    /// Returns the original value.
+    ///
+    /// # Safety
+    ///
+    /// `arena` must be the one used to create this value.
+    #[inline]
+    pub unsafe fn extract(self, arena: &Arena) -> T {
+        ManuallyDrop::into_inner(self.to_compact(arena)).extract()
+    }
+
+    /// Returns a reference type that acts like `&T`.
+    ///
+    /// # Safety
+    ///
+    /// `arena` must be the one used to create this value.
+    #[inline]
+    pub unsafe fn resolve<'a>(&'a self, arena: &'a Arena) -> <T as CompactBorrow>::Target<'a>
+    where
+        T: CompactBorrow,
+    {
+        transmute(T::borrow(&self.to_compact(arena)))
+    }
+
+    #[inline]
+    unsafe fn to_compact(&self, arena: &Arena) -> ManuallyDrop<Compact<T>> {
+        let tag = self.tag();
+        let payload = self.data as usize - tag;
+        let ptr = match T::TARGETS[tag] {
+            Some(_) => arena.as_ptr().wrapping_add(payload),
+            None => core::ptr::null::<u8>().wrapping_add(payload),
+        };
+        transmute_copy(&ptr.wrapping_add(tag))
+    }
+}
+
+impl<T: CompactRelocatable + Copy> Clone for Compact32<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: CompactRelocatable + Copy> Copy for Compact32<T> {}
+
+unsafe impl<T: CompactRelocatable + Send> Send for Compact32<T> {}
+
+unsafe impl<T: CompactRelocatable + Sync> Sync for Compact32<T> {}
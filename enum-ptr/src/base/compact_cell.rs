@@ -0,0 +1,189 @@
+use core::cell::{Cell, UnsafeCell};
+use core::mem::replace;
+
+use crate::{Compact, CompactBorrow, CompactInnerCopy, Compactable};
+
+/// Mutable memory location holding a [`Compact<T>`], for single-threaded
+/// interior mutability.
+///
+/// Unlike `Cell<Compact<T>>`, the value can be inspected in place by
+/// [`tag`](Self::tag) and [`with_borrow`](Self::with_borrow). Replacing the
+/// value while it is borrowed panics.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use std::rc::Rc;
+///
+/// use enum_ptr::{Compact, CompactCell, EnumPtr, Unit};
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(borrow)]
+/// #[repr(C, usize)]
+/// enum Child {
+///     Node(Rc<Node>),
+///     Text(Box<String>),
+///     #[enum_ptr(skip)]
+///     Empty(Unit),
+/// }
+///
+/// struct Node {
+///     child: CompactCell<Child>,
+/// }
+///
+/// let node = Rc::new(Node {
+///     child: CompactCell::new(Child::Empty(Unit::new()).into()),
+/// });
+/// node.child.set(Child::Text(Box::new("hello".into())).into());
+/// assert_eq!(node.child.tag(), 1);
+///
+/// let len = node.child.with_borrow(|child| match child {
+///     ChildRef::Text(text) => text.len(),
+///     _ => 0,
+/// });
+/// assert_eq!(len, 5);
+///
+/// let old = node.child.replace(Child::Empty(Unit::new()).into());
+/// assert!(matches!(old.extract(), Child::Text(text) if *text == "hello"));
+/// # }
+/// ```
+pub struct CompactCell<T: Compactable> {
+    value: UnsafeCell<Compact<T>>,
+    borrows: Cell<usize>,
+}
+
+impl<T: Compactable> CompactCell<T> {
+    /// Creates a new cell containing `value`.
+    #[inline]
+    pub fn new(value: Compact<T>) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            borrows: Cell::new(0),
+        }
+    }
+
+    /// Returns the tag of the contained value without moving it.
+    #[inline]
+    pub fn tag(&self) -> usize {
+        unsafe { (*self.value.get()).tag() }
+    }
+
+    /// Sets the contained value and drops the old one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is currently borrowed.
+    #[inline]
+    pub fn set(&self, value: Compact<T>) {
+        drop(self.replace(value));
+    }
+
+    /// Replaces the contained value with `value`, and returns the old one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is currently borrowed.
+    #[inline]
+    pub fn replace(&self, value: Compact<T>) -> Compact<T> {
+        self.assert_not_borrowed();
+        unsafe { replace(&mut *self.value.get(), value) }
+    }
+
+    /// Takes the contained value, leaving `Default::default()` in its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is currently borrowed.
+    #[inline]
+    pub fn take(&self) -> Compact<T>
+    where
+        T: Default,
+    {
+        self.replace(Compact::default())
+    }
+
+    /// Swaps the values of two cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either value is currently borrowed.
+    #[inline]
+    pub fn swap(&self, other: &Self) {
+        if core::ptr::eq(self, other) {
+            return;
+        }
+        self.assert_not_borrowed();
+        other.assert_not_borrowed();
+        unsafe { core::ptr::swap(self.value.get(), other.value.get()) }
+    }
+
+    /// Calls `f` with a reference type that acts like `&T`.
+    ///
+    /// The value cannot be replaced until `f` returns.
+    #[inline]
+    pub fn with_borrow<R>(&self, f: impl FnOnce(<T as CompactBorrow>::Target<'_>) -> R) -> R
+    where
+        T: CompactBorrow,
+    {
+        struct Release<'a>(&'a Cell<usize>);
+
+        impl Drop for Release<'_> {
+            #[inline]
+            fn drop(&mut self) {
+                self.0.set(self.0.get() - 1);
+            }
+        }
+
+        self.borrows.set(self.borrows.get() + 1);
+        let _release = Release(&self.borrows);
+        f(unsafe { (*self.value.get()).borrow() })
+    }
+
+    /// Returns a mutable reference to the contained value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Compact<T> {
+        self.value.get_mut()
+    }
+
+    /// Consumes the cell and returns the contained value.
+    #[inline]
+    pub fn into_inner(self) -> Compact<T> {
+        self.value.into_inner()
+    }
+
+    #[inline]
+    fn assert_not_borrowed(&self) {
+        assert!(self.borrows.get() == 0, "value is currently borrowed");
+    }
+}
+
+impl<T: Compactable<Inner = CompactInnerCopy<T>> + Copy> CompactCell<T> {
+    /// Returns a copy of the contained value.
+    #[inline]
+    pub fn get(&self) -> Compact<T> {
+        unsafe { *self.value.get() }
+    }
+}
+
+impl<T: Compactable> From<Compact<T>> for CompactCell<T> {
+    #[inline]
+    fn from(value: Compact<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Compactable + Default> Default for CompactCell<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Compact::default())
+    }
+}
+
+impl<T: Compactable> core::fmt::Debug for CompactCell<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CompactCell")
+            .field("tag", &self.tag())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,252 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use super::atomic_versioned::low_bits;
+use crate::{Compact, Compactable};
+
+/// Mutual exclusion lock of [`Compact<T>`] whose lock flag lives in the first
+/// bit above the tag. Only one-pointer wide.
+///
+/// Every variant must have an alignment greater than `T::MASK + 1`, which is
+/// checked at compile time. Waiting threads spin with exponential backoff,
+/// then yield to the OS scheduler if the `std` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::{CompactMutex, EnumPtr};
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(borrow_mut)]
+/// #[repr(C, usize)]
+/// enum Child {
+///     Leaf(Box<u64>),
+///     Node(Box<[u64; 2]>),
+/// }
+///
+/// let child = CompactMutex::new(Child::Leaf(Box::new(0)).into());
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             if let ChildRefMut::Leaf(n) = child.lock().borrow_mut() {
+///                 *n += 1;
+///             }
+///         });
+///     }
+/// });
+///
+/// let mut guard = child.try_lock().unwrap();
+/// assert!(child.try_lock().is_none());
+/// assert!(matches!(guard.borrow_mut(), ChildRefMut::Leaf(n) if *n == 4));
+/// *guard = Child::Node(Box::new([1, 2])).into();
+/// drop(guard);
+/// assert_eq!(child.into_inner().tag(), 1);
+/// # }
+/// ```
+pub struct CompactMutex<T: Compactable> {
+    data: AtomicPtr<u8>,
+    marker: PhantomData<UnsafeCell<Compact<T>>>,
+}
+
+impl<T: Compactable> CompactMutex<T> {
+    const LOCKED: usize = {
+        assert!(
+            low_bits::<T>() > T::TAG_BITS,
+            "no spare bit for the lock flag"
+        );
+        1 << T::TAG_BITS
+    };
+
+    /// Creates a new unlocked mutex.
+    #[inline]
+    pub fn new(value: Compact<T>) -> Self {
+        let _ = Self::LOCKED;
+        Self {
+            data: AtomicPtr::new(value.into_raw() as *mut u8),
+            marker: PhantomData,
+        }
+    }
+
+    /// Acquires the lock, spinning until it is available.
+    pub fn lock(&self) -> CompactMutexGuard<'_, T> {
+        let mut backoff = Backoff::new();
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+            while self.is_locked() {
+                backoff.snooze();
+            }
+        }
+    }
+
+    /// Attempts to acquire the lock without blocking.
+    #[inline]
+    pub fn try_lock(&self) -> Option<CompactMutexGuard<'_, T>> {
+        let word = self.data.load(Ordering::Relaxed);
+        if word as usize & Self::LOCKED != 0 {
+            return None;
+        }
+        self.data
+            .compare_exchange(
+                word,
+                word.wrapping_add(Self::LOCKED),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .ok()
+            .map(|word| CompactMutexGuard {
+                mutex: self,
+                value: ManuallyDrop::new(unsafe { Compact::from_raw(word as *mut ()) }),
+            })
+    }
+
+    /// Returns whether the mutex is currently locked.
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        self.data.load(Ordering::Relaxed) as usize & Self::LOCKED != 0
+    }
+
+    /// Returns a mutable reference to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a guard has been forgotten, as its value may be stale.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Compact<T> {
+        let word = self.data.get_mut();
+        assert!(
+            *word as usize & Self::LOCKED == 0,
+            "a guard has been forgotten"
+        );
+        unsafe { &mut *(word as *mut *mut u8 as *mut Compact<T>) }
+    }
+
+    /// Consumes the mutex and returns the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a guard has been forgotten, as its value may be stale. The
+    /// value is leaked in that case.
+    #[inline]
+    pub fn into_inner(self) -> Compact<T> {
+        let mut this = ManuallyDrop::new(self);
+        let word = *this.data.get_mut();
+        assert!(
+            word as usize & Self::LOCKED == 0,
+            "a guard has been forgotten"
+        );
+        unsafe { Compact::from_raw(word as *mut ()) }
+    }
+}
+
+impl<T: Compactable> Drop for CompactMutex<T> {
+    /// Leaks the value if a guard has been forgotten, as it may be stale.
+    #[inline]
+    fn drop(&mut self) {
+        let word = *self.data.get_mut();
+        if word as usize & Self::LOCKED == 0 {
+            drop(unsafe { Compact::<T>::from_raw(word as *mut ()) });
+        }
+    }
+}
+
+impl<T: Compactable> From<Compact<T>> for CompactMutex<T> {
+    #[inline]
+    fn from(value: Compact<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Compactable + Default> Default for CompactMutex<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Compact::default())
+    }
+}
+
+impl<T: Compactable + core::fmt::Debug> core::fmt::Debug for CompactMutex<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("CompactMutex");
+        match self.try_lock() {
+            Some(guard) => d.field(&*guard),
+            None => d.field(&format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
+
+unsafe impl<T: Compactable + Send> Sync for CompactMutex<T> {}
+
+/// Guard returned by [`CompactMutex::lock`]. It dereferences to
+/// [`Compact<T>`], and releases the lock when dropped.
+pub struct CompactMutexGuard<'a, T: Compactable> {
+    mutex: &'a CompactMutex<T>,
+    value: ManuallyDrop<Compact<T>>,
+}
+
+impl<T: Compactable> Deref for CompactMutexGuard<'_, T> {
+    type Target = Compact<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Compactable> DerefMut for CompactMutexGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: Compactable> Drop for CompactMutexGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        self.mutex
+            .data
+            .store(value.into_raw() as *mut u8, Ordering::Release);
+    }
+}
+
+impl<T: Compactable + core::fmt::Debug> core::fmt::Debug for CompactMutexGuard<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Spin-then-yield backoff for waiting on the lock.
+struct Backoff {
+    step: u32,
+}
+
+impl Backoff {
+    const SPIN_LIMIT: u32 = 6;
+
+    #[inline]
+    fn new() -> Self {
+        Self { step: 0 }
+    }
+
+    #[inline]
+    fn snooze(&mut self) {
+        if self.step <= Self::SPIN_LIMIT {
+            for _ in 0..1 << self.step {
+                core::hint::spin_loop();
+            }
+            self.step += 1;
+        } else {
+            #[cfg(feature = "std")]
+            std::thread::yield_now();
+            #[cfg(not(feature = "std"))]
+            core::hint::spin_loop();
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use core::marker::PhantomData;
+use core::mem::{transmute, ManuallyDrop};
+use core::ops::Deref;
+
+use crate::{Compact, CompactBorrow, Compactable};
+
+/// Borrowed handle of [`Compact<T>`]. Only one-pointer wide and always
+/// [`Copy`].
+///
+/// Unlike `&Compact<T>`, it holds the compact value itself rather than a
+/// pointer to it, so borrowing through it does not need another indirection.
+///
+/// It dereferences to [`Compact<T>`], thus [`get_ref`](crate::get_ref) and
+/// [`map_ref`](Compact::map_ref) also work on it.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::{get_ref, Compact, CompactRef, EnumPtr};
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(borrow)]
+/// #[repr(C, usize)]
+/// enum Foo {
+///     A(Box<i32>),
+///     B(Box<u32>),
+/// }
+///
+/// let foo: Compact<_> = Foo::A(Box::new(1)).into();
+/// let foo_ref = CompactRef::new(&foo);
+/// let copied = foo_ref;
+/// assert_eq!(foo_ref.tag(), 0);
+/// assert_eq!(get_ref!(copied, Foo::A), Some(&1));
+/// match copied.borrow() {
+///     FooRef::A(inner) => assert_eq!(inner, &1),
+///     _ => unreachable!(),
+/// }
+/// # }
+/// ```
+#[repr(transparent)]
+pub struct CompactRef<'a, T: Compactable> {
+    data: *const u8,
+    marker: PhantomData<&'a Compact<T>>,
+}
+
+impl<'a, T: Compactable> CompactRef<'a, T> {
+    /// Creates a borrowed handle from a reference.
+    #[inline]
+    pub fn new(compact: &'a Compact<T>) -> Self {
+        Self {
+            data: compact.as_raw_data(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference type that acts like `&'a T`.
+    ///
+    /// Unlike [`Compact::borrow`], the result is bound to `'a` rather than to
+    /// this handle.
+    #[inline]
+    pub fn borrow(self) -> <T as CompactBorrow>::Target<'a>
+    where
+        T: CompactBorrow,
+    {
+        // SAFETY: `FieldDeref` forbids targets pointing to the field's own
+        // memory, so the targets stay valid as long as the compact value.
+        unsafe { transmute(T::borrow(&self)) }
+    }
+}
+
+impl<T: Compactable> Deref for CompactRef<'_, T> {
+    type Target = Compact<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // `CompactRef` and `Compact` share the same representation.
+        unsafe { &*(self as *const Self as *const ManuallyDrop<Compact<T>>) }
+    }
+}
+
+impl<'a, T: Compactable> From<&'a Compact<T>> for CompactRef<'a, T> {
+    #[inline]
+    fn from(compact: &'a Compact<T>) -> Self {
+        Self::new(compact)
+    }
+}
+
+impl<T: Compactable> Clone for CompactRef<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Compactable> Copy for CompactRef<'_, T> {}
+
+impl<T: Compactable + core::fmt::Debug> core::fmt::Debug for CompactRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&**self, f)
+    }
+}
+
+unsafe impl<T: Compactable + Sync> Send for CompactRef<'_, T> {}
+
+unsafe impl<T: Compactable + Sync> Sync for CompactRef<'_, T> {}
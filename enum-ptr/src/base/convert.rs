@@ -1,3 +1,4 @@
+use core::alloc::Layout;
 use core::mem::{transmute_copy, ManuallyDrop};
 
 use crate::{Compact, CompactInner, CompactInnerCopy};
@@ -13,31 +14,84 @@ mod private {
     impl<T: Compactable<Inner = Self>> Sealed for CompactInner<T> {}
 
     impl<T: Compactable<Inner = Self> + Copy> Sealed for CompactInnerCopy<T> {}
+
+    #[cfg(feature = "alloc")]
+    impl<T: crate::DeepDrop<Inner = Self>> Sealed for crate::CompactInnerDeep<T> {}
 }
 
-#[doc(hidden)]
+/// Types that can be stored in [`Compact`]. Derived by
+/// [`EnumPtr`](crate::EnumPtr).
+///
+/// Besides the conversions, it exposes the layout of the compact
+/// representation at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use enum_ptr::{Compact, Compactable, EnumPtr, Unit};
+///
+/// #[derive(EnumPtr)]
+/// #[repr(C, usize)]
+/// enum Foo<'a> {
+///     A(&'a u64),
+///     B(&'a u32),
+///     C(Unit),
+/// }
+///
+/// assert_eq!(Foo::VARIANT_COUNT, 3);
+/// assert_eq!(Foo::TAG_BITS, 2);
+/// assert_eq!(Foo::MASK, 0b11);
+/// assert_eq!(Foo::VARIANT_NAMES, ["A", "B", "C"]);
+/// assert_eq!(Foo::VARIANT_ALIGNMENTS[..2], [8, 4]);
+///
+/// let foo: Compact<_> = Foo::B(&1).into();
+/// assert_eq!(foo.variant_name(), "B");
+/// ```
+///
+/// # Safety
+///
+/// `Self` must be a `#[repr(C, usize)]` enum whose variants each have exactly
+/// one pointer-sized field, with enough alignment to store the tag.
 pub unsafe trait Compactable: Sized {
+    #[doc(hidden)]
     type Inner: private::Sealed;
 
+    /// Number of variants. Tags range from `0` to `VARIANT_COUNT - 1`.
+    const VARIANT_COUNT: usize;
+
+    /// Mask of the tag in the compact representation.
     const MASK: usize;
 
+    /// Number of low bits occupied by the tag.
+    const TAG_BITS: u32 = Self::MASK.count_ones();
+
+    /// Names of variants, indexed by tag.
+    const VARIANT_NAMES: &'static [&'static str];
+
+    /// Alignments of variant fields, indexed by tag.
+    const VARIANT_ALIGNMENTS: &'static [usize];
+
+    /// Converts `self` into its compact representation.
     #[inline]
     fn compact(self) -> Compact<Self> {
         let inner = self.compact_inner();
         Compact { inner }
     }
 
+    #[doc(hidden)]
     #[inline]
     fn compact_inner(self) -> Self::Inner {
         let PtrRepr(tag, ptr) = unsafe { transmute_copy(&ManuallyDrop::new(self)) };
         unsafe { transmute_copy(&ptr.wrapping_add(tag)) }
     }
 
+    /// Converts the compact representation back.
     #[inline]
     fn extract(value: Compact<Self>) -> Self {
         Self::extract_inner(value.inner)
     }
 
+    #[doc(hidden)]
     #[inline]
     fn extract_inner(value: Self::Inner) -> Self {
         let value: *const u8 = unsafe { transmute_copy(&ManuallyDrop::new(value)) };
@@ -46,3 +100,8 @@ pub unsafe trait Compactable: Sized {
         unsafe { transmute_copy(&PtrRepr(tag, ptr)) }
     }
 }
+
+#[doc(hidden)]
+pub unsafe trait CompactRelocatable: Compactable {
+    const TARGETS: &'static [Option<Layout>];
+}
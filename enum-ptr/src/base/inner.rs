@@ -2,6 +2,8 @@ use core::marker::PhantomData;
 use core::mem::ManuallyDrop;
 
 use crate::Compactable;
+#[cfg(feature = "alloc")]
+use crate::DeepDrop;
 
 #[doc(hidden)]
 #[repr(transparent)]
@@ -24,6 +26,38 @@ impl<T: Compactable<Inner = Self> + Clone> Clone for CompactInner<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[repr(transparent)]
+pub struct CompactInnerDeep<T: DeepDrop<Inner = Self>> {
+    _data: *const u8,
+    marker: PhantomData<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: DeepDrop<Inner = Self>> Drop for CompactInnerDeep<T> {
+    fn drop(&mut self) {
+        let mut stack = alloc::vec::Vec::new();
+        let mut value = T::extract_inner(Self { ..*self });
+        loop {
+            value.take_children(&mut stack);
+            drop(value);
+            match stack.pop() {
+                Some(child) => value = child.extract(),
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: DeepDrop<Inner = Self> + Clone> Clone for CompactInnerDeep<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        T::clone(&ManuallyDrop::new(T::extract_inner(Self { ..*self }))).compact_inner()
+    }
+}
+
 #[doc(hidden)]
 #[derive(Clone, Copy)]
 #[repr(transparent)]
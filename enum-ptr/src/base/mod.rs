@@ -1,7 +1,26 @@
+mod arc_swap;
+mod atomic_versioned;
 mod compact;
+#[cfg(feature = "alloc")]
+mod compact32;
+mod compact_cell;
+mod compact_mutex;
+mod compact_ref;
 mod convert;
 mod inner;
+mod once_compact;
+mod rel_compact;
+mod tags;
 
+pub use arc_swap::*;
+pub use atomic_versioned::*;
 pub use compact::*;
+#[cfg(feature = "alloc")]
+pub use compact32::*;
+pub use compact_cell::*;
+pub use compact_mutex::*;
+pub use compact_ref::*;
 pub use convert::*;
 pub use inner::*;
+pub use once_compact::*;
+pub use rel_compact::*;
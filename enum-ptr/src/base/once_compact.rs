@@ -0,0 +1,317 @@
+use core::cell::{Cell, UnsafeCell};
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use super::atomic_versioned::low_bits;
+use crate::{Compact, CompactRef, Compactable};
+
+/// Word of the uninitialized state. It is the unused tag `MASK` if any, then
+/// the first bit above the tag if spare, otherwise the null encoding of the
+/// first variant.
+#[inline]
+const fn uninit<T: Compactable>() -> *mut u8 {
+    let word = if T::VARIANT_COUNT <= T::MASK {
+        T::MASK
+    } else if low_bits::<T>() > T::TAG_BITS {
+        1 << T::TAG_BITS
+    } else {
+        0
+    };
+    core::ptr::null_mut::<u8>().wrapping_add(word)
+}
+
+#[inline]
+fn into_word<T: Compactable>(value: Compact<T>) -> *mut u8 {
+    assert!(
+        value.as_raw_data() != uninit::<T>(),
+        "value collides with the uninitialized state"
+    );
+    value.into_raw() as *mut u8
+}
+
+/// Cell of [`Compact<T>`] that can be written only once. Only one-pointer
+/// wide.
+///
+/// The uninitialized state is encoded by an unused tag if the number of
+/// variants is not a power of two, or else by a spare bit above the tag if
+/// every variant is aligned enough, or else by a null first variant. In the
+/// last case, storing a null first variant (e.g., `None`) panics.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::{get_ref, EnumPtr, OnceCompact};
+///
+/// #[derive(EnumPtr)]
+/// #[repr(C, usize)]
+/// enum Foo {
+///     A(Box<i32>),
+///     B(Box<u32>),
+/// }
+///
+/// let cell = OnceCompact::<Foo>::new();
+/// assert!(cell.get().is_none());
+/// assert_eq!(core::mem::size_of_val(&cell), core::mem::size_of::<usize>());
+///
+/// let foo = cell.get_or_init(|| Foo::A(Box::new(1)).into());
+/// assert_eq!(get_ref!(foo, Foo::A), Some(&1));
+/// assert!(cell.set(Foo::B(Box::new(2)).into()).is_err());
+/// assert!(matches!(cell.into_inner().map(|c| c.extract()), Some(Foo::A(_))));
+/// # }
+/// ```
+///
+/// A null first variant can be stored when a spare bit is available:
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::{EnumPtr, OnceCompact, Unit};
+///
+/// #[derive(EnumPtr)]
+/// #[repr(C, usize)]
+/// enum Slot {
+///     Empty(Unit),
+///     Full(Box<u64>),
+/// }
+///
+/// let cell = OnceCompact::<Slot>::new();
+/// assert!(cell.set(Slot::Empty(Unit::new()).into()).is_ok());
+/// assert_eq!(cell.get().unwrap().tag(), 0);
+/// # }
+/// ```
+pub struct OnceCompact<T: Compactable> {
+    data: Cell<*mut u8>,
+    marker: PhantomData<Compact<T>>,
+}
+
+impl<T: Compactable> OnceCompact<T> {
+    /// Creates a new uninitialized cell.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            data: Cell::new(uninit::<T>()),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the value if initialized.
+    #[inline]
+    pub fn get(&self) -> Option<CompactRef<'_, T>> {
+        let word = self.data.get();
+        (word != uninit::<T>()).then(|| unsafe { Compact::ref_from_raw(word as *const ()) })
+    }
+
+    /// Returns a mutable reference to the value if initialized.
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut Compact<T>> {
+        let word = self.data.get_mut();
+        (*word != uninit::<T>()).then(|| unsafe { &mut *(word as *mut *mut u8 as *mut Compact<T>) })
+    }
+
+    /// Initializes the cell with `value`, or returns it back if the cell is
+    /// already initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` collides with the uninitialized state.
+    #[inline]
+    pub fn set(&self, value: Compact<T>) -> Result<(), Compact<T>> {
+        if self.get().is_some() {
+            return Err(value);
+        }
+        self.data.set(into_word(value));
+        Ok(())
+    }
+
+    /// Returns the value, initializing it with `f` if uninitialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` initializes the cell reentrantly, or the value collides
+    /// with the uninitialized state.
+    #[inline]
+    pub fn get_or_init(&self, f: impl FnOnce() -> Compact<T>) -> CompactRef<'_, T> {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let value = f();
+        assert!(self.set(value).is_ok(), "reentrant init");
+        self.get().unwrap()
+    }
+
+    /// Takes the value out, leaving the cell uninitialized.
+    #[inline]
+    pub fn take(&mut self) -> Option<Compact<T>> {
+        let word = self.data.replace(uninit::<T>());
+        (word != uninit::<T>()).then(|| unsafe { Compact::from_raw(word as *mut ()) })
+    }
+
+    /// Consumes the cell and returns the value if initialized.
+    #[inline]
+    pub fn into_inner(mut self) -> Option<Compact<T>> {
+        self.take()
+    }
+}
+
+impl<T: Compactable> Drop for OnceCompact<T> {
+    #[inline]
+    fn drop(&mut self) {
+        drop(self.take());
+    }
+}
+
+impl<T: Compactable> Default for OnceCompact<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Compactable + core::fmt::Debug> core::fmt::Debug for OnceCompact<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("OnceCompact");
+        match self.get() {
+            Some(value) => d.field(&*value),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+unsafe impl<T: Compactable + Send> Send for OnceCompact<T> {}
+
+/// Thread-safe version of [`OnceCompact`]. Only one-pointer wide.
+///
+/// If multiple threads initialize it at the same time, only one value is
+/// stored and the others are dropped.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use std::sync::Arc;
+///
+/// use enum_ptr::{get_ref, AtomicOnceCompact, EnumPtr};
+///
+/// #[derive(EnumPtr)]
+/// #[repr(C, usize)]
+/// enum Foo {
+///     A(Arc<i32>),
+///     B(Arc<u32>),
+/// }
+///
+/// static CELL: AtomicOnceCompact<Foo> = AtomicOnceCompact::new();
+///
+/// std::thread::scope(|s| {
+///     for i in 0..4 {
+///         s.spawn(move || CELL.get_or_init(|| Foo::A(Arc::new(i)).into()));
+///     }
+/// });
+/// assert!(get_ref!(CELL.get().unwrap(), Foo::A).is_some());
+/// # }
+/// ```
+pub struct AtomicOnceCompact<T: Compactable> {
+    data: AtomicPtr<u8>,
+    marker: PhantomData<UnsafeCell<Compact<T>>>,
+}
+
+impl<T: Compactable> AtomicOnceCompact<T> {
+    /// Creates a new uninitialized cell.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            data: AtomicPtr::new(uninit::<T>()),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the value if initialized.
+    #[inline]
+    pub fn get(&self) -> Option<CompactRef<'_, T>> {
+        let word = self.data.load(Ordering::Acquire);
+        (word != uninit::<T>()).then(|| unsafe { Compact::ref_from_raw(word as *const ()) })
+    }
+
+    /// Returns a mutable reference to the value if initialized.
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut Compact<T>> {
+        let word = self.data.get_mut();
+        (*word != uninit::<T>()).then(|| unsafe { &mut *(word as *mut *mut u8 as *mut Compact<T>) })
+    }
+
+    /// Initializes the cell with `value`, or returns it back if the cell is
+    /// already initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` collides with the uninitialized state.
+    #[inline]
+    pub fn set(&self, value: Compact<T>) -> Result<(), Compact<T>> {
+        let word = into_word(value);
+        match self
+            .data
+            .compare_exchange(uninit::<T>(), word, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => Ok(()),
+            Err(_) => Err(unsafe { Compact::from_raw(word as *mut ()) }),
+        }
+    }
+
+    /// Returns the value, initializing it with `f` if uninitialized.
+    ///
+    /// `f` may be called by multiple threads at the same time, in which case
+    /// only one result is stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value collides with the uninitialized state.
+    #[inline]
+    pub fn get_or_init(&self, f: impl FnOnce() -> Compact<T>) -> CompactRef<'_, T> {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        drop(self.set(f()));
+        self.get().unwrap()
+    }
+
+    /// Takes the value out, leaving the cell uninitialized.
+    #[inline]
+    pub fn take(&mut self) -> Option<Compact<T>> {
+        let word = core::mem::replace(self.data.get_mut(), uninit::<T>());
+        (word != uninit::<T>()).then(|| unsafe { Compact::from_raw(word as *mut ()) })
+    }
+
+    /// Consumes the cell and returns the value if initialized.
+    #[inline]
+    pub fn into_inner(self) -> Option<Compact<T>> {
+        ManuallyDrop::new(self).take()
+    }
+}
+
+impl<T: Compactable> Drop for AtomicOnceCompact<T> {
+    #[inline]
+    fn drop(&mut self) {
+        drop(self.take());
+    }
+}
+
+impl<T: Compactable> Default for AtomicOnceCompact<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Compactable + core::fmt::Debug> core::fmt::Debug for AtomicOnceCompact<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("AtomicOnceCompact");
+        match self.get() {
+            Some(value) => d.field(&*value),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+unsafe impl<T: Compactable + Send + Sync> Sync for AtomicOnceCompact<T> {}
@@ -0,0 +1,212 @@
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of, transmute, transmute_copy, ManuallyDrop};
+
+use crate::{Compact, CompactBorrow, CompactRelocatable};
+
+/// Compact representation of `T` relative to its own address. Only
+/// one-pointer wide.
+///
+/// Addresses are stored as offsets from the address of this value, while
+/// other values (e.g., [`Unit`](crate::Unit)) are stored as they are. Both of
+/// them share the low bits with the tag, just like [`Compact`]. Therefore, a
+/// buffer containing both this value and its targets can be copied or mapped
+/// elsewhere, and then be borrowed without deserialization.
+///
+/// It requires `#[enum_ptr(relocatable)]`, and all fields to implement
+/// [`Relocatable`](crate::Relocatable).
+///
+/// # Examples
+///
+/// ```
+/// use enum_ptr::{EnumPtr, RelCompact, RelCompactError, Unit};
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(relocatable, borrow)]
+/// #[repr(C, usize)]
+/// enum Node<'a> {
+///     Leaf(&'a i64),
+///     #[enum_ptr(skip)]
+///     Empty(Unit),
+/// }
+///
+/// #[repr(align(8))]
+/// struct Buf([u8; 32]);
+///
+/// // Put an `i64` at offset 8 and a pointer to it at offset 0.
+/// let mut buf = Buf([0; 32]);
+/// buf.0[8..16].copy_from_slice(&42i64.to_ne_bytes());
+/// let base = buf.0.as_ptr();
+/// let leaf = unsafe { &*(base.add(8) as *const i64) };
+/// let rel = RelCompact::new_at(Node::Leaf(leaf), base.cast()).ok().unwrap();
+/// buf.0[..8].copy_from_slice(&rel.as_raw_data().to_ne_bytes());
+///
+/// // The buffer is still valid after being copied, e.g., to a mapped file.
+/// let copy = Buf(buf.0);
+/// match unsafe { RelCompact::<Node>::view(&copy.0, 0) } {
+///     Ok(NodeRef::Leaf(leaf)) => assert_eq!(*leaf, 42),
+///     _ => unreachable!(),
+/// }
+///
+/// // Offsets that point out of the buffer are rejected.
+/// let result = unsafe { RelCompact::<Node>::view(&copy.0, 8) };
+/// assert_eq!(result.err(), Some(RelCompactError::OutOfBounds));
+/// ```
+#[repr(transparent)]
+pub struct RelCompact<T: CompactRelocatable> {
+    data: usize,
+    marker: PhantomData<T>,
+}
+
+/// Error returned when a [`RelCompact`] fails validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelCompactError {
+    /// The value or its target is out of the buffer.
+    OutOfBounds,
+    /// The value or its target is not properly aligned.
+    Misaligned,
+    /// The tag does not correspond to any variant.
+    InvalidTag,
+}
+
+impl core::fmt::Display for RelCompactError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::OutOfBounds => "relative pointer is out of bounds",
+            Self::Misaligned => "relative pointer is misaligned",
+            Self::InvalidTag => "relative pointer has an invalid tag",
+        })
+    }
+}
+
+impl core::error::Error for RelCompactError {}
+
+impl<T: CompactRelocatable> RelCompact<T> {
+    /// Creates a compact value that is only meaningful when stored at `at`,
+    /// or returns the value back if an offset collides with the tag.
+    ///
+    /// `at` is only used for its address and is never dereferenced.
+    pub fn new_at(value: T, at: *const Self) -> Result<Self, T> {
+        let compact = ManuallyDrop::new(value.compact());
+        let tag = compact.tag();
+        let ptr = compact.as_raw_data().wrapping_sub(tag);
+        let payload = match T::TARGETS[tag] {
+            Some(_) => ptr.expose_provenance().wrapping_sub(at as usize),
+            None => ptr as usize,
+        };
+        match payload & T::MASK {
+            0 => Ok(Self {
+                data: payload | tag,
+                marker: PhantomData,
+            }),
+            _ => Err(ManuallyDrop::into_inner(compact).extract()),
+        }
+    }
+
+    /// Replaces the stored value with `value`, relative to the current
+    /// address, or returns the value back if an offset collides with the tag.
+    #[inline]
+    pub fn set(&mut self, value: T) -> Result<(), T> {
+        *self = Self::new_at(value, self)?;
+        Ok(())
+    }
+
+    /// Returns the underlying raw data.
+    #[inline]
+    pub fn as_raw_data(&self) -> usize {
+        self.data
+    }
+
+    /// Returns the tag, i.e., the index of the current variant.
+    #[inline]
+    pub fn tag(&self) -> usize {
+        self.data & T::MASK
+    }
+
+    /// Checks that this value lies in `buf`, has a valid tag, and its target,
+    /// if any, lies in `buf` and is properly aligned.
+    pub fn validate(&self, buf: &[u8]) -> Result<(), RelCompactError> {
+        let pos = (self as *const Self as usize).wrapping_sub(buf.as_ptr() as usize);
+        if pos > buf.len() || buf.len() - pos < size_of::<Self>() {
+            return Err(RelCompactError::OutOfBounds);
+        }
+        let tag = self.tag();
+        let Some(target) = T::TARGETS.get(tag) else {
+            return Err(RelCompactError::InvalidTag);
+        };
+        if let Some(layout) = target {
+            let offset = pos.wrapping_add(self.data - tag);
+            if offset > buf.len() || buf.len() - offset < layout.size() {
+                return Err(RelCompactError::OutOfBounds);
+            }
+            if (buf.as_ptr() as usize + offset) & (layout.align() - 1) != 0 {
+                return Err(RelCompactError::Misaligned);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a reference type that acts like `&T`.
+    ///
+    /// # Safety
+    ///
+    /// This value must not have been moved since created by
+    /// [`new_at`](Self::new_at) or [`set`](Self::set), unless together with
+    /// its targets, and its targets must still be alive.
+    #[inline]
+    pub unsafe fn borrow(&self) -> <T as CompactBorrow>::Target<'_>
+    where
+        T: CompactBorrow,
+    {
+        self.borrow_at(core::ptr::with_exposed_provenance(
+            self as *const Self as usize,
+        ))
+    }
+
+    /// Validates the value at offset `pos` of `buf` and returns a reference
+    /// type that acts like `&T`.
+    ///
+    /// # Safety
+    ///
+    /// Targets in `buf` must be valid values of their types.
+    pub unsafe fn view(
+        buf: &[u8],
+        pos: usize,
+    ) -> Result<<T as CompactBorrow>::Target<'_>, RelCompactError>
+    where
+        T: CompactBorrow,
+    {
+        if pos > buf.len() || buf.len() - pos < size_of::<Self>() {
+            return Err(RelCompactError::OutOfBounds);
+        }
+        let base = buf.as_ptr().add(pos);
+        if base as usize & (align_of::<Self>() - 1) != 0 {
+            return Err(RelCompactError::Misaligned);
+        }
+        let this = &*(base as *const Self);
+        this.validate(buf)?;
+        Ok(this.borrow_at(base))
+    }
+
+    #[inline]
+    unsafe fn borrow_at<'a>(&self, base: *const u8) -> <T as CompactBorrow>::Target<'a>
+    where
+        T: CompactBorrow,
+    {
+        transmute(T::borrow(&self.to_compact(base)))
+    }
+
+    #[inline]
+    unsafe fn to_compact(&self, base: *const u8) -> ManuallyDrop<Compact<T>> {
+        let tag = self.tag();
+        let payload = self.data - tag;
+        let ptr = match T::TARGETS[tag] {
+            Some(_) => base.wrapping_add(payload),
+            None => core::ptr::null::<u8>().wrapping_add(payload),
+        };
+        transmute_copy(&ptr.wrapping_add(tag))
+    }
+}
+
+unsafe impl<T: CompactRelocatable + Send> Send for RelCompact<T> {}
+
+unsafe impl<T: CompactRelocatable + Sync> Sync for RelCompact<T> {}
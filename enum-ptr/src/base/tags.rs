@@ -0,0 +1,132 @@
+use crate::{Compact, CompactRef, Compactable};
+
+// Bulk operations on tags. They only read the tag bits and never extract the
+// values.
+impl<T: Compactable> Compact<T> {
+    /// Counts compact values of each variant.
+    ///
+    /// `N` must equal [`Compactable::VARIANT_COUNT`], which is checked at
+    /// compile time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use enum_ptr::{Compact, EnumPtr, Unit};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[repr(C, usize)]
+    /// enum Foo<'a> {
+    ///     A(&'a i64),
+    ///     B(&'a u64),
+    ///     C(Unit),
+    /// }
+    ///
+    /// let slice: [Compact<_>; 3] = [
+    ///     Foo::A(&1).into(),
+    ///     Foo::C(Unit::new()).into(),
+    ///     Foo::A(&2).into(),
+    /// ];
+    /// assert_eq!(Compact::count_tags(&slice), [2, 0, 1]);
+    /// ```
+    pub fn count_tags<const N: usize>(slice: &[Self]) -> [usize; N] {
+        const {
+            assert!(
+                N == T::VARIANT_COUNT,
+                "`N` differs from the number of variants"
+            );
+        }
+        let mut counts = [0; N];
+        for compact in slice {
+            counts[compact.tag()] += 1;
+        }
+        counts
+    }
+
+    /// Reorders `slice` so that compact values with `tag` come first, and
+    /// returns the number of them. The order is not preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use enum_ptr::{Compact, EnumPtr, Unit};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[repr(C, usize)]
+    /// enum Foo<'a> {
+    ///     A(&'a i64),
+    ///     B(Unit),
+    /// }
+    ///
+    /// let mut slice: [Compact<_>; 3] = [
+    ///     Foo::B(Unit::new()).into(),
+    ///     Foo::A(&1).into(),
+    ///     Foo::A(&2).into(),
+    /// ];
+    /// let count = Compact::partition_by_tag(&mut slice, 0);
+    /// assert_eq!(count, 2);
+    /// assert!(slice[..count].iter().all(|compact| compact.tag() == 0));
+    /// assert_eq!(slice[count].tag(), 1);
+    /// ```
+    pub fn partition_by_tag(slice: &mut [Self], tag: usize) -> usize {
+        let mut count = 0;
+        for i in 0..slice.len() {
+            if slice[i].tag() == tag {
+                slice.swap(count, i);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Retains only the compact values whose tags satisfy `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// use enum_ptr::{Compact, EnumPtr, Unit};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[repr(C, usize)]
+    /// enum Foo<'a> {
+    ///     A(&'a i64),
+    ///     B(Unit),
+    /// }
+    ///
+    /// let mut vec: Vec<Compact<_>> = vec![Foo::B(Unit::new()).into(), Foo::A(&1).into()];
+    /// Compact::retain_tag(&mut vec, |tag| tag != 1);
+    /// assert_eq!(vec.len(), 1);
+    /// assert_eq!(vec[0].tag(), 0);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn retain_tag(vec: &mut alloc::vec::Vec<Self>, mut f: impl FnMut(usize) -> bool) {
+        vec.retain(|compact| f(compact.tag()));
+    }
+
+    /// Returns an iterator over tags and borrowed handles of compact values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use enum_ptr::{get_ref, Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[repr(C, usize)]
+    /// enum Foo<'a> {
+    ///     A(&'a i64),
+    ///     B(&'a u64),
+    /// }
+    ///
+    /// let slice: [Compact<_>; 2] = [Foo::B(&1).into(), Foo::A(&2).into()];
+    /// let mut iter = Compact::iter_tags(&slice).filter(|(tag, _)| *tag == 0);
+    /// let (_, foo) = iter.next().unwrap();
+    /// assert_eq!(get_ref!(foo, Foo::A), Some(&2));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn iter_tags(slice: &[Self]) -> impl Iterator<Item = (usize, CompactRef<'_, T>)> {
+        slice
+            .iter()
+            .map(|compact| (compact.tag(), CompactRef::new(compact)))
+    }
+}
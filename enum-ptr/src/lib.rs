@@ -107,6 +107,10 @@
 //!
 //! Check the documentation of [`EnumPtr`] for more details.
 //!
+//! Derived reference types are two pointers wide. If you want to store
+//! borrowed values, [`CompactRef`] is a one-pointer-wide [`Copy`] handle that
+//! can be borrowed later.
+//!
 //! <details>
 //! <summary>Click to show examples</summary>
 //!
@@ -195,6 +199,9 @@
 //! - To make your types available in [`get_ref`] / [`get_mut`] and
 //!   `#[enum_ptr(borrow)]` / `#[enum_ptr(borrow_mut)]`, implement
 //!   [`FieldDeref`] / [`FieldDerefMut`].
+//! - If your types are newtypes of supported types, derive
+//!   [`Aligned`](macro@Aligned), [`FieldDeref`](macro@FieldDeref) and
+//!   [`FieldDerefMut`](macro@FieldDerefMut) instead.
 //! - Unsatisfied with derived reference types? Implement [`CompactBorrow`] /
 //!   [`CompactBorrowMut`] by hand.
 //!
@@ -206,6 +213,7 @@
 //!   - According to the [RFC] and the [Rust Reference], `#[repr(C, usize)]`
 //!     guarantees the memory layout and discriminant values. Thus, we can
 //!     safely transmute between two representations.
+//!   - [`compact`](macro@compact) adds it for you.
 //! - **Each variant of `Foo` must have exactly one field.**
 //!   - Unit variants are not allowed due to performance concerns.
 //!   - If you need a unit variant, use [`Unit`].
@@ -220,11 +228,18 @@
 //! # Features
 //!
 //! - `alloc` *(default)* --- `Box`, `Rc` and `Arc` support
+//! - `std` --- yielding to the OS scheduler in [`CompactMutex`]
+//! - `allocator_api` *(nightly)* --- `Box<T, A>` support for zero-sized
+//!   allocators `A`
+//! - `bumpalo` --- `bumpalo::boxed::Box` support
 
 #![no_std]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 mod base;
 mod traits;
@@ -245,6 +260,8 @@ pub use utils::*;
 /// #[derive(EnumPtr)]
 /// #[enum_ptr(
 ///     // copy,    // derives conversions to and from `CompactCopy`
+///     // iterative_drop, // drops without recursion, requires `DeepDrop`
+///     // relocatable, // derives support of `Compact32` and `RelCompact`
 ///     borrow(     // derives a reference type and `impl CompactBorrow`
 ///         name = "FooRef",     // default: ident + "Ref"
 ///         derive(Clone, Copy), // default: none
@@ -253,6 +270,10 @@ pub use utils::*;
 ///         name = "FooRefMut",  // default: ident + "RefMut"
 ///         derive(Debug),       // default: none
 ///     ),
+///     // as_dyn(Debug),     // derives `impl CompactAsDyn<dyn Debug>`
+///     // as_dyn_mut(Debug), // derives `impl CompactAsDynMut<dyn Debug>`
+///     // c_header(prefix = "FOO"), // generates `Foo::C_HEADER`
+///     // match_macro, // generates `FooTag` and `match_foo!`
 /// )]
 /// #[repr(C, usize)]
 /// enum Foo {
@@ -267,4 +288,146 @@ pub use utils::*;
 /// }
 /// # }
 /// ```
+///
+/// `as_dyn` / `as_dyn_mut` require the targets of `FieldDeref` /
+/// `FieldDerefMut` of all variants to be coercible to the trait objects.
+///
+/// `c_header` generates a C header with the tag of each variant, the mask and
+/// `tag_of` / `untag` functions, so that C code can inspect compact values
+/// without hard-coding them. The prefix defaults to the ident in
+/// `SCREAMING_SNAKE_CASE`.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::EnumPtr;
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(c_header)]
+/// #[repr(C, usize)]
+/// enum NetMsg {
+///     Ping(Box<u64>),
+///     SendData(Box<[u64; 2]>),
+/// }
+///
+/// assert!(NetMsg::C_HEADER.contains("#define NET_MSG_TAG_SEND_DATA ((uintptr_t)1)"));
+/// assert!(NetMsg::C_HEADER.contains("#define NET_MSG_MASK ((uintptr_t)1)"));
+/// assert!(NetMsg::C_HEADER.contains("static inline void *net_msg_untag("));
+/// # }
+/// ```
+///
+/// `match_macro` generates a tag enum `FooTag` and a `match_foo!` macro that
+/// dispatches on the tag without extracting the whole value. Each arm binds
+/// the target of `FieldDeref` (or `FieldDerefMut` with the `mut` form), and
+/// missing variants are reported by the exhaustiveness check of `FooTag`. The
+/// macro follows the scoping of `macro_rules!`, and requires `Foo` and
+/// `FooTag` to be in scope.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::{Compact, EnumPtr};
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(match_macro)]
+/// #[repr(C, usize)]
+/// enum Shape {
+///     Circle(Box<f64>),
+///     Rect(Box<(f64, f64)>),
+///     Empty(Option<Box<u32>>),
+/// }
+///
+/// fn area(shape: &Compact<Shape>) -> f64 {
+///     match_shape!(shape, {
+///         Circle(r) => 3.0 * r * r,
+///         Rect(&(w, h)) => w * h,
+///         _ => 0.0,
+///     })
+/// }
+///
+/// let mut shape: Compact<_> = Shape::Rect(Box::new((2.0, 3.0))).into();
+/// assert_eq!(ShapeTag::of(&shape), ShapeTag::Rect);
+/// assert_eq!(area(&shape), 6.0);
+///
+/// match_shape!(mut shape, {
+///     Circle(r) => *r = 0.0,
+///     Rect(rect) => rect.0 = 1.0,
+///     Empty(_) => {}
+/// });
+/// assert_eq!(area(&shape), 3.0);
+/// # }
+/// ```
 pub use enum_ptr_derive::EnumPtr;
+
+/// Shorthand for `#[derive(EnumPtr)]`, `#[enum_ptr(...)]` and
+/// `#[repr(C, usize)]`.
+///
+/// It accepts the same options as [`EnumPtr`]. Other `repr`s are rejected.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::{compact, Compact};
+///
+/// #[compact(borrow, copy)]
+/// #[derive(Clone, Copy)]
+/// enum Foo<'a> {
+///     A(&'a i64),
+///     B(&'a u64),
+/// }
+///
+/// let foo: Compact<_> = Foo::A(&1).into();
+/// assert!(matches!(foo.borrow(), FooRef::A(&1)));
+/// # }
+/// ```
+pub use enum_ptr_derive::compact;
+
+/// Derives [`Aligned`](trait@Aligned) for a newtype by forwarding to its field.
+///
+/// The newtype must have a `#[repr(transparent)]`. Use `#[aligned(min = N)]`
+/// to check at compile time that the field has an alignment of at least `N`.
+/// The derived alignment is still the one of the field.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::{Aligned, Compact, EnumPtr, FieldDeref, FieldDerefMut};
+///
+/// #[derive(Aligned, FieldDeref, FieldDerefMut)]
+/// #[repr(transparent)]
+/// struct MyBox<T>(Box<T>);
+///
+/// #[derive(Aligned, FieldDeref)]
+/// #[aligned(min = 4)]
+/// #[repr(transparent)]
+/// struct MyRef<'a, T> {
+///     inner: &'a T,
+/// }
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(borrow)]
+/// #[repr(C, usize)]
+/// enum Foo<'a> {
+///     A(MyBox<i64>),
+///     B(MyRef<'a, u32>),
+/// }
+///
+/// let foo: Compact<_> = Foo::A(MyBox(Box::new(1))).into();
+/// assert!(matches!(foo.borrow(), FooRef::A(&1)));
+/// # }
+/// ```
+pub use enum_ptr_derive::Aligned;
+
+/// Derives [`FieldDeref`](trait@FieldDeref) for a newtype by forwarding to its
+/// field.
+///
+/// The newtype must have a `#[repr(transparent)]`. Check
+/// [`Aligned`](macro@Aligned) for examples.
+pub use enum_ptr_derive::FieldDeref;
+
+/// Derives [`FieldDerefMut`](trait@FieldDerefMut) for a newtype by forwarding
+/// to its field.
+///
+/// The newtype must have a `#[repr(transparent)]`. Check
+/// [`Aligned`](macro@Aligned) for examples.
+pub use enum_ptr_derive::FieldDerefMut;
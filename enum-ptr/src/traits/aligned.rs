@@ -1,4 +1,5 @@
 use core::mem::align_of;
+use core::pin::Pin;
 
 /// Types (may not be pointers) that can be used in [`EnumPtr`](crate::EnumPtr).
 ///
@@ -8,7 +9,8 @@ use core::mem::align_of;
 /// - `T`'s pointee must be aligned by `ALIGNMENT` (`T`'s low bits are zeros).
 ///
 /// For example, raw pointers are not guaranteed to be aligned, so implementing
-/// this trait for them is unsound.
+/// this trait for them is unsound. Use [`CheckedPtr`](crate::CheckedPtr) to
+/// check their alignment at runtime instead.
 ///
 /// # Examples
 ///
@@ -49,10 +51,16 @@ unsafe impl<T> Aligned for Option<&mut T> {
     const ALIGNMENT: usize = align_of::<T>();
 }
 
+/// [`Pin`] is `repr(transparent)`, so it shares the alignment of `P`.
+unsafe impl<P: Aligned> Aligned for Pin<P> {
+    const ALIGNMENT: usize = P::ALIGNMENT;
+}
+
 #[cfg(feature = "alloc")]
 mod alloc_impl {
     use super::*;
 
+    #[cfg(not(feature = "allocator_api"))]
     use alloc::boxed::Box;
     use alloc::rc::Rc;
     use alloc::sync::Arc;
@@ -66,6 +74,7 @@ mod alloc_impl {
         }
     }
 
+    #[cfg(not(feature = "allocator_api"))]
     unsafe impl<T> Aligned for Box<T> {
         const ALIGNMENT: usize = align_of::<T>();
     }
@@ -100,6 +109,7 @@ mod alloc_impl {
         const ALIGNMENT: usize = max(align_of::<T>(), align_of::<usize>());
     }
 
+    #[cfg(not(feature = "allocator_api"))]
     unsafe impl<T> Aligned for Option<Box<T>> {
         const ALIGNMENT: usize = align_of::<T>();
     }
@@ -128,3 +138,86 @@ mod alloc_impl {
         const ALIGNMENT: usize = max(align_of::<T>(), align_of::<usize>());
     }
 }
+
+#[cfg(feature = "allocator_api")]
+mod allocator_api_impl {
+    use super::*;
+
+    use alloc::alloc::Allocator;
+    use alloc::boxed::Box;
+    use core::mem::size_of;
+
+    /// Only zero-sized allocators are supported. Otherwise, `Box<T, A>` is
+    /// wider than one pointer. Since `A` is zero-sized, it can be recreated
+    /// from nothing when the value is extracted, so dropping a compact value
+    /// still deallocates through `A`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+    /// # #[cfg(feature = "allocator_api")] {
+    /// use std::alloc::Global;
+    ///
+    /// use enum_ptr::{get_ref, Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[repr(C, usize)]
+    /// enum Foo {
+    ///     A(Box<i32, Global>),
+    ///     B(Box<u32, Global>),
+    /// }
+    ///
+    /// let foo: Compact<_> = Foo::A(Box::new_in(1, Global)).into();
+    /// assert_eq!(get_ref!(foo, Foo::A), Some(&1));
+    /// # }
+    /// ```
+    unsafe impl<T, A: Allocator> Aligned for Box<T, A> {
+        const ALIGNMENT: usize = {
+            assert!(size_of::<A>() == 0, "`A` is not zero-sized");
+            align_of::<T>()
+        };
+    }
+
+    unsafe impl<T, A: Allocator> Aligned for Option<Box<T, A>> {
+        const ALIGNMENT: usize = {
+            assert!(size_of::<A>() == 0, "`A` is not zero-sized");
+            align_of::<T>()
+        };
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+mod bumpalo_impl {
+    use super::*;
+
+    use bumpalo::boxed::Box;
+
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "bumpalo")] {
+    /// use bumpalo::boxed::Box;
+    /// use bumpalo::Bump;
+    /// use enum_ptr::{get_ref, Compact, EnumPtr};
+    ///
+    /// #[derive(EnumPtr)]
+    /// #[repr(C, usize)]
+    /// enum Node<'a> {
+    ///     A(Box<'a, i32>),
+    ///     B(Box<'a, u32>),
+    /// }
+    ///
+    /// let bump = Bump::new();
+    /// let node: Compact<_> = Node::A(Box::new_in(1, &bump)).into();
+    /// assert_eq!(get_ref!(node, Node::A), Some(&1));
+    /// # }
+    /// ```
+    unsafe impl<T> Aligned for Box<'_, T> {
+        const ALIGNMENT: usize = align_of::<T>();
+    }
+
+    unsafe impl<T> Aligned for Option<Box<'_, T>> {
+        const ALIGNMENT: usize = align_of::<T>();
+    }
+}
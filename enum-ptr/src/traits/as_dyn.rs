@@ -0,0 +1,21 @@
+use crate::{Compact, Compactable};
+
+/// Types whose [`Compact`] form can be viewed as a trait object `D`.
+/// Typically derived from [`EnumPtr`](crate::EnumPtr) with
+/// `#[enum_ptr(as_dyn(...))]`.
+///
+/// The trait object points to **the object that the field points to**, i.e.,
+/// the target of [`FieldDeref`](crate::FieldDeref).
+pub trait CompactAsDyn<D: ?Sized>: Compactable {
+    fn as_dyn(compact: &Compact<Self>) -> &D;
+}
+
+/// Types whose [`Compact`] form can be viewed as a mutable trait object `D`.
+/// Typically derived from [`EnumPtr`](crate::EnumPtr) with
+/// `#[enum_ptr(as_dyn_mut(...))]`.
+///
+/// The trait object points to **the object that the field points to**, i.e.,
+/// the target of [`FieldDerefMut`](crate::FieldDerefMut).
+pub trait CompactAsDynMut<D: ?Sized>: Compactable {
+    fn as_dyn_mut(compact: &mut Compact<Self>) -> &mut D;
+}
@@ -1,5 +1,6 @@
 use core::mem::transmute;
 use core::ops::Deref;
+use core::pin::Pin;
 
 use crate::{Compact, Compactable};
 
@@ -81,14 +82,56 @@ unsafe impl<T> FieldDeref for Option<&mut T> {
     }
 }
 
+/// Borrowing a pinned pointer keeps the pointee pinned.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use core::future::{ready, Future};
+/// use core::pin::Pin;
+/// use core::task::{Context, Poll, Waker};
+///
+/// use enum_ptr::{Compact, EnumPtr, Unit};
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(borrow_mut)]
+/// #[repr(C, usize)]
+/// enum Task<F: Future> {
+///     Pending(Pin<Box<F>>),
+///     #[enum_ptr(skip)]
+///     Done(Unit),
+/// }
+///
+/// let mut task: Compact<_> = Task::Pending(Box::pin(ready(1u64))).into();
+/// let mut cx = Context::from_waker(Waker::noop());
+/// match task.borrow_mut() {
+///     TaskRefMut::Pending(fut) => assert_eq!(fut.poll(&mut cx), Poll::Ready(1)),
+///     _ => unreachable!(),
+/// }
+/// # }
+/// ```
+unsafe impl<P: Deref + FieldDeref> FieldDeref for Pin<P> {
+    type Target<'a> = Pin<&'a <P as Deref>::Target>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn deref(&self) -> Self::Target<'_> {
+        self.as_ref()
+    }
+}
+
 #[cfg(feature = "alloc")]
 mod alloc_impl {
     use super::*;
 
+    #[cfg(not(feature = "allocator_api"))]
     use alloc::boxed::Box;
     use alloc::rc::Rc;
     use alloc::sync::Arc;
 
+    #[cfg(not(feature = "allocator_api"))]
     unsafe impl<T> FieldDeref for Box<T> {
         type Target<'a> = &'a T
         where
@@ -122,6 +165,7 @@ mod alloc_impl {
         }
     }
 
+    #[cfg(not(feature = "allocator_api"))]
     unsafe impl<T> FieldDeref for Option<Box<T>> {
         type Target<'a> = Option<&'a T>
         where
@@ -155,3 +199,62 @@ mod alloc_impl {
         }
     }
 }
+
+#[cfg(feature = "allocator_api")]
+mod allocator_api_impl {
+    use super::*;
+
+    use alloc::alloc::Allocator;
+    use alloc::boxed::Box;
+
+    unsafe impl<T, A: Allocator> FieldDeref for Box<T, A> {
+        type Target<'a> = &'a T
+        where
+            Self: 'a;
+
+        #[inline]
+        fn deref(&self) -> Self::Target<'_> {
+            Deref::deref(self)
+        }
+    }
+
+    unsafe impl<T, A: Allocator> FieldDeref for Option<Box<T, A>> {
+        type Target<'a> = Option<&'a T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn deref(&self) -> Self::Target<'_> {
+            self.as_deref()
+        }
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+mod bumpalo_impl {
+    use super::*;
+
+    use bumpalo::boxed::Box;
+
+    unsafe impl<T> FieldDeref for Box<'_, T> {
+        type Target<'a> = &'a T
+        where
+            Self: 'a;
+
+        #[inline]
+        fn deref(&self) -> Self::Target<'_> {
+            Deref::deref(self)
+        }
+    }
+
+    unsafe impl<T> FieldDeref for Option<Box<'_, T>> {
+        type Target<'a> = Option<&'a T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn deref(&self) -> Self::Target<'_> {
+            self.as_deref()
+        }
+    }
+}
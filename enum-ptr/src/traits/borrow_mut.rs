@@ -1,5 +1,6 @@
 use core::mem::transmute;
-use core::ops::DerefMut;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
 
 use crate::{Compact, Compactable};
 
@@ -59,7 +60,20 @@ unsafe impl<T> FieldDerefMut for Option<&mut T> {
     }
 }
 
-#[cfg(feature = "alloc")]
+/// Mutably borrowing a pinned pointer yields `Pin<&mut T>` rather than
+/// `&mut T`, so the pointee stays pinned.
+unsafe impl<P: DerefMut + FieldDerefMut> FieldDerefMut for Pin<P> {
+    type Target<'a> = Pin<&'a mut <P as Deref>::Target>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn deref_mut(&mut self) -> Self::Target<'_> {
+        self.as_mut()
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "allocator_api")))]
 mod alloc_impl {
     use super::*;
 
@@ -87,3 +101,62 @@ mod alloc_impl {
         }
     }
 }
+
+#[cfg(feature = "allocator_api")]
+mod allocator_api_impl {
+    use super::*;
+
+    use alloc::alloc::Allocator;
+    use alloc::boxed::Box;
+
+    unsafe impl<T, A: Allocator> FieldDerefMut for Box<T, A> {
+        type Target<'a> = &'a mut T
+        where
+            Self: 'a;
+
+        #[inline]
+        fn deref_mut(&mut self) -> Self::Target<'_> {
+            DerefMut::deref_mut(self)
+        }
+    }
+
+    unsafe impl<T, A: Allocator> FieldDerefMut for Option<Box<T, A>> {
+        type Target<'a> = Option<&'a mut T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn deref_mut(&mut self) -> Self::Target<'_> {
+            self.as_deref_mut()
+        }
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+mod bumpalo_impl {
+    use super::*;
+
+    use bumpalo::boxed::Box;
+
+    unsafe impl<T> FieldDerefMut for Box<'_, T> {
+        type Target<'a> = &'a mut T
+        where
+            Self: 'a;
+
+        #[inline]
+        fn deref_mut(&mut self) -> Self::Target<'_> {
+            DerefMut::deref_mut(self)
+        }
+    }
+
+    unsafe impl<T> FieldDerefMut for Option<Box<'_, T>> {
+        type Target<'a> = Option<&'a mut T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn deref_mut(&mut self) -> Self::Target<'_> {
+            self.as_deref_mut()
+        }
+    }
+}
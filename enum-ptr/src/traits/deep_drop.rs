@@ -0,0 +1,44 @@
+use alloc::vec::Vec;
+
+use crate::{Compact, Compactable};
+
+/// Types that can be dropped without recursion. Required by
+/// `#[enum_ptr(iterative_drop)]`.
+///
+/// When a [`Compact`] of such a type is dropped, children are moved into a
+/// worklist before their parent is freed, so that deep structures (e.g., long
+/// linked lists) do not overflow the stack.
+///
+/// # Examples
+///
+/// ```
+/// use enum_ptr::{Compact, DeepDrop, EnumPtr, Unit};
+///
+/// #[derive(EnumPtr)]
+/// #[enum_ptr(iterative_drop)]
+/// #[repr(C, usize)]
+/// enum List {
+///     Cons(Box<(i32, Compact<List>)>),
+///     Nil(Unit),
+/// }
+///
+/// impl DeepDrop for List {
+///     fn take_children(&mut self, stack: &mut Vec<Compact<Self>>) {
+///         if let List::Cons(node) = self {
+///             let nil = List::Nil(Unit::new()).into();
+///             stack.push(std::mem::replace(&mut node.1, nil));
+///         }
+///     }
+/// }
+///
+/// let mut list: Compact<_> = List::Nil(Unit::new()).into();
+/// for i in 0..1_000_000 {
+///     list = List::Cons(Box::new((i, list))).into();
+/// }
+/// drop(list);
+/// ```
+pub trait DeepDrop: Compactable {
+    /// Moves child compact values out of `self` into `stack`, leaving cheap
+    /// placeholders behind.
+    fn take_children(&mut self, stack: &mut Vec<Compact<Self>>);
+}
@@ -1,7 +1,15 @@
 mod aligned;
+mod as_dyn;
 mod borrow;
 mod borrow_mut;
+#[cfg(feature = "alloc")]
+mod deep_drop;
+mod relocatable;
 
 pub use aligned::*;
+pub use as_dyn::*;
 pub use borrow::*;
 pub use borrow_mut::*;
+#[cfg(feature = "alloc")]
+pub use deep_drop::*;
+pub use relocatable::*;
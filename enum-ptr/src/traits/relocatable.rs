@@ -0,0 +1,36 @@
+use core::alloc::Layout;
+
+use crate::{Aligned, ShiftUsize, Unit};
+
+/// Types that can be stored relative to a base address. Required by
+/// `#[enum_ptr(relocatable)]`.
+///
+/// Addresses are stored as offsets from the base, while other values are
+/// stored as they are.
+///
+/// # Safety
+///
+/// - If `TARGET` is `Some`, `T` must be a non-null pointer to a value with
+///   that layout, and it must be valid to recreate `T` from its address.
+/// - Otherwise, `T` must be valid for any bit pattern that it can produce.
+/// - `T` must not need to be dropped.
+pub unsafe trait Relocatable: Aligned {
+    /// Layout of the pointee if `T` holds an address.
+    const TARGET: Option<Layout>;
+}
+
+unsafe impl<T> Relocatable for &T {
+    const TARGET: Option<Layout> = Some(Layout::new::<T>());
+}
+
+unsafe impl<T> Relocatable for &mut T {
+    const TARGET: Option<Layout> = Some(Layout::new::<T>());
+}
+
+unsafe impl Relocatable for Unit {
+    const TARGET: Option<Layout> = None;
+}
+
+unsafe impl<const N: usize> Relocatable for ShiftUsize<N> {
+    const TARGET: Option<Layout> = None;
+}
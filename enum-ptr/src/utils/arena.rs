@@ -0,0 +1,144 @@
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use core::cell::Cell;
+use core::mem::{align_of, size_of};
+use core::ptr::NonNull;
+
+/// Fixed-capacity bump allocator. Addresses in it can be stored as 32-bit
+/// offsets by [`Compact32`](crate::Compact32).
+///
+/// The capacity is fixed so that allocated values are never moved. Like other
+/// bump allocators, it does not run destructors of allocated values.
+///
+/// # Examples
+///
+/// ```
+/// use enum_ptr::Arena;
+///
+/// let arena = Arena::with_capacity(64);
+/// let a = arena.alloc(1u64);
+/// let b = arena.alloc(2u32);
+/// assert_eq!((*a, *b), (1, 2));
+/// assert!(arena.try_alloc([0u8; 64]).is_err());
+/// ```
+pub struct Arena {
+    ptr: NonNull<u8>,
+    capacity: usize,
+    len: Cell<usize>,
+}
+
+impl Arena {
+    /// Alignment of the start of the arena.
+    pub const ALIGNMENT: usize = 64;
+
+    /// Creates an arena that can hold `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` exceeds [`u32::MAX`].
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity <= u32::MAX as usize, "capacity exceeds `u32::MAX`");
+        let ptr = match capacity {
+            0 => NonNull::new(Self::ALIGNMENT as *mut u8).unwrap(),
+            _ => {
+                let layout = Self::layout(capacity);
+                NonNull::new(unsafe { alloc(layout) }).unwrap_or_else(|| handle_alloc_error(layout))
+            }
+        };
+        Self {
+            ptr,
+            capacity,
+            len: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of bytes the arena can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of bytes used, including paddings.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if nothing has been allocated.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the start address of the arena.
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    /// Moves `value` into the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena has no enough space.
+    #[allow(clippy::mut_from_ref)]
+    #[inline]
+    pub fn alloc<U>(&self, value: U) -> &mut U {
+        match self.try_alloc(value) {
+            Ok(value) => value,
+            Err(_) => panic!("arena has no enough space"),
+        }
+    }
+
+    /// Moves `value` into the arena, or returns it back if the arena has no
+    /// enough space.
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc<U>(&self, value: U) -> Result<&mut U, U> {
+        let start = self.len.get();
+        let padding = self.ptr.as_ptr().wrapping_add(start).align_offset(align_of::<U>());
+        let offset = match start.checked_add(padding) {
+            Some(offset) if offset <= self.capacity => offset,
+            _ => return Err(value),
+        };
+        if self.capacity - offset < size_of::<U>() {
+            return Err(value);
+        }
+        self.len.set(offset + size_of::<U>());
+        unsafe {
+            let ptr = self.ptr.as_ptr().add(offset) as *mut U;
+            ptr.write(value);
+            Ok(&mut *ptr)
+        }
+    }
+
+    /// Returns the offset of `ptr` if `size` bytes from it lie in the used
+    /// region of the arena.
+    #[inline]
+    pub(crate) fn offset_of(&self, ptr: *const u8, size: usize) -> Option<usize> {
+        let offset = (ptr as usize).wrapping_sub(self.ptr.as_ptr() as usize);
+        let len = self.len.get();
+        (offset < len && size <= len - offset).then_some(offset)
+    }
+
+    fn layout(capacity: usize) -> Layout {
+        Layout::from_size_align(capacity, Self::ALIGNMENT).unwrap()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        if self.capacity != 0 {
+            unsafe { dealloc(self.ptr.as_ptr(), Self::layout(self.capacity)) }
+        }
+    }
+}
+
+impl core::fmt::Debug for Arena {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Arena")
+            .field("capacity", &self.capacity)
+            .field("len", &self.len.get())
+            .finish()
+    }
+}
+
+unsafe impl Send for Arena {}
@@ -0,0 +1,189 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::transmute_copy;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::Aligned;
+
+/// Atomic pointer with a `BITS`-bit tag in its low bits. Only one-pointer
+/// wide.
+///
+/// The tag bits come from [`Aligned::ALIGNMENT`], so `1 << BITS` must not
+/// exceed it. Only the low `BITS` bits of tag arguments are used.
+///
+/// # Examples
+///
+/// Marking a successor as logically deleted, as in Harris' linked list:
+///
+/// ```
+/// use core::sync::atomic::Ordering::SeqCst;
+///
+/// use enum_ptr::AtomicTaggedPtr;
+///
+/// const DELETED: usize = 1;
+///
+/// let (a, b) = (1u64, 2u64);
+/// let next = AtomicTaggedPtr::<&u64, 1>::new(&a, 0);
+///
+/// let (ptr, tag) = next.fetch_or_tag(DELETED, SeqCst);
+/// assert_eq!((*ptr, tag), (1, 0));
+/// assert_eq!(next.load_tag(SeqCst), DELETED);
+///
+/// // A marked successor cannot be swung.
+/// assert!(next
+///     .compare_exchange_with_tag(&a, 0, &b, 0, SeqCst, SeqCst)
+///     .is_err());
+///
+/// next.fetch_and_tag(!DELETED, SeqCst);
+/// assert!(next
+///     .compare_exchange_with_tag(&a, 0, &b, 0, SeqCst, SeqCst)
+///     .is_ok());
+/// assert_eq!(*next.load_ptr(SeqCst), 2);
+/// ```
+pub struct AtomicTaggedPtr<P: Aligned + Copy, const BITS: u32> {
+    data: AtomicPtr<u8>,
+    marker: PhantomData<UnsafeCell<P>>,
+}
+
+impl<P: Aligned + Copy, const BITS: u32> AtomicTaggedPtr<P, BITS> {
+    /// Mask of the tag bits.
+    pub const TAG_MASK: usize = {
+        assert!(
+            BITS < usize::BITS && 1 << BITS <= P::ALIGNMENT,
+            "`BITS` exceeds the alignment of `P`"
+        );
+        (1 << BITS) - 1
+    };
+
+    /// Creates a new atomic pointer.
+    #[inline]
+    pub fn new(ptr: P, tag: usize) -> Self {
+        Self {
+            data: AtomicPtr::new(Self::pack(ptr, tag)),
+            marker: PhantomData,
+        }
+    }
+
+    /// Loads the pointer and the tag.
+    #[inline]
+    pub fn load(&self, order: Ordering) -> (P, usize) {
+        Self::unpack(self.data.load(order))
+    }
+
+    /// Loads the pointer.
+    #[inline]
+    pub fn load_ptr(&self, order: Ordering) -> P {
+        self.load(order).0
+    }
+
+    /// Loads the tag.
+    #[inline]
+    pub fn load_tag(&self, order: Ordering) -> usize {
+        self.data.load(order) as usize & Self::TAG_MASK
+    }
+
+    /// Stores the pointer and the tag.
+    #[inline]
+    pub fn store(&self, ptr: P, tag: usize, order: Ordering) {
+        self.data.store(Self::pack(ptr, tag), order);
+    }
+
+    /// Stores the pointer and the tag, returning the previous ones.
+    #[inline]
+    pub fn swap(&self, ptr: P, tag: usize, order: Ordering) -> (P, usize) {
+        Self::unpack(self.data.swap(Self::pack(ptr, tag), order))
+    }
+
+    /// Bitwise "or" of the tag with `tag`, returning the previous pointer and
+    /// tag.
+    #[inline]
+    pub fn fetch_or_tag(&self, tag: usize, order: Ordering) -> (P, usize) {
+        Self::unpack(self.data.fetch_or(tag & Self::TAG_MASK, order))
+    }
+
+    /// Bitwise "and" of the tag with `tag`, returning the previous pointer and
+    /// tag.
+    #[inline]
+    pub fn fetch_and_tag(&self, tag: usize, order: Ordering) -> (P, usize) {
+        Self::unpack(self.data.fetch_and(tag | !Self::TAG_MASK, order))
+    }
+
+    /// Stores `new_ptr` and `new_tag` if the current pointer and tag are
+    /// `expected_ptr` and `expected_tag`. Returns the previous ones in either
+    /// case.
+    #[inline]
+    pub fn compare_exchange_with_tag(
+        &self,
+        expected_ptr: P,
+        expected_tag: usize,
+        new_ptr: P,
+        new_tag: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<(P, usize), (P, usize)> {
+        self.data
+            .compare_exchange(
+                Self::pack(expected_ptr, expected_tag),
+                Self::pack(new_ptr, new_tag),
+                success,
+                failure,
+            )
+            .map(Self::unpack)
+            .map_err(Self::unpack)
+    }
+
+    /// Like [`compare_exchange_with_tag`](Self::compare_exchange_with_tag),
+    /// but may fail spuriously.
+    #[inline]
+    pub fn compare_exchange_weak_with_tag(
+        &self,
+        expected_ptr: P,
+        expected_tag: usize,
+        new_ptr: P,
+        new_tag: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<(P, usize), (P, usize)> {
+        self.data
+            .compare_exchange_weak(
+                Self::pack(expected_ptr, expected_tag),
+                Self::pack(new_ptr, new_tag),
+                success,
+                failure,
+            )
+            .map(Self::unpack)
+            .map_err(Self::unpack)
+    }
+
+    /// Consumes the atomic pointer and returns the pointer and the tag.
+    #[inline]
+    pub fn into_inner(self) -> (P, usize) {
+        Self::unpack(self.data.into_inner())
+    }
+
+    #[inline]
+    fn pack(ptr: P, tag: usize) -> *mut u8 {
+        let ptr: *mut u8 = unsafe { transmute_copy(&ptr) };
+        ptr.wrapping_add(tag & Self::TAG_MASK)
+    }
+
+    #[inline]
+    fn unpack(word: *mut u8) -> (P, usize) {
+        let tag = word as usize & Self::TAG_MASK;
+        (unsafe { transmute_copy(&word.wrapping_sub(tag)) }, tag)
+    }
+}
+
+impl<P: Aligned + Copy + core::fmt::Debug, const BITS: u32> core::fmt::Debug
+    for AtomicTaggedPtr<P, BITS>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (ptr, tag) = self.load(Ordering::Relaxed);
+        f.debug_struct("AtomicTaggedPtr")
+            .field("ptr", &ptr)
+            .field("tag", &tag)
+            .finish()
+    }
+}
+
+unsafe impl<P: Aligned + Copy + Send + Sync, const BITS: u32> Sync for AtomicTaggedPtr<P, BITS> {}
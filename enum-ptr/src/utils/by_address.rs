@@ -0,0 +1,93 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, DerefMut};
+
+use crate::{Compact, Compactable};
+
+/// Wrapper that compares and hashes [`Compact<T>`] by its raw data (the
+/// payload address and the tag), instead of by the pointees.
+///
+/// It is useful as keys of maps of interned or shared nodes, where comparing
+/// the pointees would be slow or wrong.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use std::collections::HashSet;
+/// use std::rc::Rc;
+///
+/// use enum_ptr::{ByAddress, Compact, EnumPtr};
+///
+/// #[derive(EnumPtr, Clone)]
+/// #[repr(C, usize)]
+/// enum Node {
+///     Leaf(Rc<i32>),
+///     Branch(Rc<[Node; 2]>),
+/// }
+///
+/// let leaf: Compact<_> = Node::Leaf(Rc::new(1)).into();
+/// let other: Compact<_> = Node::Leaf(Rc::new(1)).into();
+///
+/// let mut visited = HashSet::new();
+/// assert!(visited.insert(ByAddress(leaf.clone())));
+/// assert!(!visited.insert(ByAddress(leaf)));
+/// assert!(visited.insert(ByAddress(other)));
+/// # }
+/// ```
+#[derive(Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct ByAddress<T>(pub T);
+
+impl<T> Deref for ByAddress<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ByAddress<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Compactable> PartialEq for ByAddress<Compact<T>> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Compact::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: Compactable> Eq for ByAddress<Compact<T>> {}
+
+impl<T: Compactable> PartialOrd for ByAddress<Compact<T>> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Compactable> Ord for ByAddress<Compact<T>> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.as_raw_data().cmp(&other.0.as_raw_data())
+    }
+}
+
+impl<T: Compactable> Hash for ByAddress<Compact<T>> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_raw_data().hash(state)
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ByAddress<T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ByAddress").field(&self.0).finish()
+    }
+}
@@ -0,0 +1,115 @@
+use core::ptr::NonNull;
+
+use crate::{Aligned, FieldDeref};
+
+/// Raw pointer whose alignment has been checked to be at least `ALIGN`.
+///
+/// Raw pointers are not guaranteed to be aligned, so they cannot be used in
+/// [`EnumPtr`](crate::EnumPtr) directly. Wrapping them with this type checks
+/// the alignment once at construction instead.
+///
+/// `ALIGN` must be a power of two.
+///
+/// # Examples
+///
+/// ```
+/// use enum_ptr::{get_ref, CheckedPtr, Compact, EnumPtr};
+///
+/// #[derive(EnumPtr)]
+/// #[repr(C, usize)]
+/// enum Foo {
+///     A(CheckedPtr<i64, 8>),
+///     B(CheckedPtr<u8, 2>),
+/// }
+///
+/// let value = 1i64;
+/// let ptr = CheckedPtr::new(&value as *const i64).unwrap();
+/// let foo: Compact<_> = Foo::A(ptr).into();
+/// assert_eq!(get_ref!(foo, Foo::A), Some(&value as *const i64));
+///
+/// let values = [0u16; 2];
+/// let unaligned = (values.as_ptr() as *const u8).wrapping_add(1);
+/// assert!(CheckedPtr::<u8, 2>::new(unaligned).is_none());
+/// ```
+#[repr(transparent)]
+pub struct CheckedPtr<T, const ALIGN: usize>(*const T);
+
+impl<T, const ALIGN: usize> CheckedPtr<T, ALIGN> {
+    /// Creates a new value if `ptr` is aligned by `ALIGN`.
+    #[inline]
+    pub fn new(ptr: *const T) -> Option<Self> {
+        let mask = <Self as Aligned>::ALIGNMENT - 1;
+        (ptr as usize & mask == 0).then_some(Self(ptr))
+    }
+
+    /// Creates a new value from a [`NonNull`] if it is aligned by `ALIGN`.
+    #[inline]
+    pub fn from_non_null(ptr: NonNull<T>) -> Option<Self> {
+        Self::new(ptr.as_ptr())
+    }
+
+    /// Creates a new value without checking the alignment.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be aligned by `ALIGN`.
+    #[inline]
+    pub unsafe fn new_unchecked(ptr: *const T) -> Self {
+        Self(ptr)
+    }
+
+    /// Returns the underlying pointer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.0
+    }
+
+    /// Returns the underlying pointer as a [`NonNull`] if it is not null.
+    #[inline]
+    pub fn as_non_null(&self) -> Option<NonNull<T>> {
+        NonNull::new(self.0 as *mut T)
+    }
+}
+
+impl<T, const ALIGN: usize> Clone for CheckedPtr<T, ALIGN> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const ALIGN: usize> Copy for CheckedPtr<T, ALIGN> {}
+
+impl<T, const ALIGN: usize> PartialEq for CheckedPtr<T, ALIGN> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T, const ALIGN: usize> Eq for CheckedPtr<T, ALIGN> {}
+
+impl<T, const ALIGN: usize> core::fmt::Debug for CheckedPtr<T, ALIGN> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("CheckedPtr").field(&self.0).finish()
+    }
+}
+
+unsafe impl<T, const ALIGN: usize> Aligned for CheckedPtr<T, ALIGN> {
+    const ALIGNMENT: usize = {
+        assert!(ALIGN.is_power_of_two(), "`ALIGN` is not a power of two");
+        ALIGN
+    };
+}
+
+unsafe impl<T, const ALIGN: usize> FieldDeref for CheckedPtr<T, ALIGN> {
+    type Target<'a> = *const T
+    where
+        Self: 'a;
+
+    #[inline]
+    fn deref(&self) -> Self::Target<'_> {
+        self.0
+    }
+}
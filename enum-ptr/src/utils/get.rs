@@ -1,3 +1,5 @@
+use core::mem::MaybeUninit;
+
 use crate::{Compact, Compactable, FieldDeref, FieldDerefMut};
 
 #[doc(hidden)]
@@ -26,10 +28,24 @@ where
     compact.map_mut(|tmp| f(tmp).map(|tmp| tmp.force_deref_mut()))
 }
 
+#[doc(hidden)]
+#[inline]
+pub fn is_variant_helper<T: Compactable>(compact: &Compact<T>) -> MaybeUninit<T> {
+    // `#[repr(C, usize)]` puts the discriminant in the first word, and the
+    // tag is that discriminant. The field is left uninitialized.
+    let mut place = MaybeUninit::<T>::uninit();
+    unsafe { place.as_mut_ptr().cast::<usize>().write(compact.tag()) };
+    place
+}
+
 /// Borrows a variant from [`Compact`].
 ///
 /// It requires the type of that variant implements [`FieldDeref`].
 ///
+/// Multiple variants with the same field type can be given as `A | B`. A
+/// predicate on the borrowed target can be given after `if`, in which case
+/// variants must be plain paths without generic arguments.
+///
 /// # Examples
 ///
 /// ```
@@ -41,19 +57,27 @@ where
 /// enum Foo {
 ///     A(Box<i32>),
 ///     B(Box<u32>),
+///     C(Box<i32>),
 /// }
 ///
 /// let foo: Compact<_> = Foo::A(Box::new(1)).into();
 /// assert_eq!(get_ref!(foo, Foo::A), Some(&1));
 /// assert_eq!(get_ref!(foo, Foo::B), None);
+/// assert_eq!(get_ref!(foo, Foo::A | Foo::C), Some(&1));
+/// assert_eq!(get_ref!(foo, Foo::A if |x| **x > 0), Some(&1));
+/// assert_eq!(get_ref!(foo, Foo::A | Foo::C if |x| **x < 0), None);
 /// # }
 /// ```
 #[macro_export]
 macro_rules! get_ref {
-    ($compact:expr, $variant:path) => {
+    ($compact:expr, $($($seg:ident)::+)|+ if $guard:expr) => {
+        $crate::get_ref!($compact, $($($seg)::+)|+).filter($guard)
+    };
+    ($compact:expr, $($variant:path)|+) => {
         unsafe {
             $crate::get_ref_helper(&$compact, |tmp| match tmp {
-                $variant(inner) => Some(inner),
+                $($variant(inner))|+ => Some(inner),
+                #[allow(unreachable_patterns)]
                 _ => None,
             })
         }
@@ -64,6 +88,8 @@ macro_rules! get_ref {
 ///
 /// It requires the type of that variant implements [`FieldDerefMut`].
 ///
+/// It accepts the same forms as [`get_ref`].
+///
 /// # Examples
 ///
 /// ```
@@ -75,21 +101,70 @@ macro_rules! get_ref {
 /// enum Foo {
 ///     A(Box<i32>),
 ///     B(Box<u32>),
+///     C(Box<i32>),
 /// }
 ///
 /// let mut foo: Compact<_> = Foo::A(Box::new(1)).into();
 /// assert_eq!(get_mut!(foo, Foo::A), Some(&mut 1));
 /// assert_eq!(get_mut!(foo, Foo::B), None);
+/// assert_eq!(get_mut!(foo, Foo::A | Foo::C), Some(&mut 1));
+/// assert_eq!(get_mut!(foo, Foo::C if |x| **x > 0), None);
 /// # }
 /// ```
 #[macro_export]
 macro_rules! get_mut {
-    ($compact:expr, $variant:path) => {
+    ($compact:expr, $($($seg:ident)::+)|+ if $guard:expr) => {
+        $crate::get_mut!($compact, $($($seg)::+)|+).filter($guard)
+    };
+    ($compact:expr, $($variant:path)|+) => {
         unsafe {
             $crate::get_mut_helper(&mut $compact, |tmp| match tmp {
-                $variant(inner) => Some(inner),
+                $($variant(inner))|+ => Some(inner),
+                #[allow(unreachable_patterns)]
                 _ => None,
             })
         }
     };
 }
+
+/// Checks whether a [`Compact`] holds one of the given variants.
+///
+/// It only inspects the tag, without extracting the value.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use enum_ptr::{is_variant, Compact, EnumPtr, Unit};
+///
+/// #[derive(EnumPtr)]
+/// #[repr(C, usize)]
+/// enum Foo {
+///     A(Box<i32>),
+///     B(Box<u32>),
+///     C(Unit),
+/// }
+///
+/// let foo: Compact<_> = Foo::C(Unit::new()).into();
+/// assert!(is_variant!(foo, Foo::C));
+/// assert!(is_variant!(foo, Foo::A | Foo::C));
+/// assert!(!is_variant!(foo, Foo::A | Foo::B));
+///
+/// use Foo::C as Empty;
+/// assert!(is_variant!(foo, Empty));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! is_variant {
+    ($compact:expr, $($variant:path)|+) => {{
+        let place = $crate::is_variant_helper(&$compact);
+        // Only the discriminant is read, as `_` does not touch the field.
+        unsafe {
+            match *place.as_ptr() {
+                $($variant(_))|+ => true,
+                #[allow(unreachable_patterns)]
+                _ => false,
+            }
+        }
+    }};
+}
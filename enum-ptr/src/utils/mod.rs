@@ -1,7 +1,17 @@
+#[cfg(feature = "alloc")]
+mod arena;
+mod atomic_tagged;
+mod by_address;
+mod checked;
 mod get;
 mod shift;
 mod unit;
 
+#[cfg(feature = "alloc")]
+pub use arena::*;
+pub use atomic_tagged::*;
+pub use by_address::*;
+pub use checked::*;
 pub use get::*;
 pub use shift::*;
 pub use unit::*;